@@ -0,0 +1,186 @@
+//! Derive macros for `rust-prelude-plus`
+//!
+//! This crate is re-exported by `rust-prelude-plus`; use `rust_prelude_plus::prelude::KeyPathSchema`
+//! rather than depending on it directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr, Member, Type};
+
+/// Generate `rust_prelude_plus::schema::KeyPathSchema` for a struct
+///
+/// See the `rust_prelude_plus::schema` module for the supported `#[keypath_schema(...)]`
+/// field attributes.
+#[proc_macro_derive(KeyPathSchema, attributes(keypath_schema))]
+pub fn derive_keypath_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    skip: bool,
+    rename: Option<String>,
+    nested: bool,
+    debug: bool,
+    serialize: bool,
+}
+
+fn field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("keypath_schema")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("nested") {
+                options.nested = true;
+            } else if meta.path.is_ident("debug") {
+                options.debug = true;
+            } else if meta.path.is_ident("serialize") {
+                options.serialize = true;
+            } else if meta.path.is_ident("rename") {
+                let name: LitStr = meta.value()?.parse()?;
+                options.rename = Some(name.value());
+            } else {
+                return Err(meta.error("expected one of `skip`, `rename`, `nested`, `debug`, `serialize`"));
+            }
+            Ok(())
+        })?;
+    }
+    if options.nested && (options.debug || options.serialize) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`nested` fields take their formatting from the nested schema",
+        ));
+    }
+    Ok(options)
+}
+
+/// Whether values of a type have no `Display` impl in std and should be formatted with `Debug`
+fn formats_with_debug(ty: &Type) -> bool {
+    match ty {
+        Type::Array(_) | Type::Slice(_) | Type::Tuple(_) => true,
+        Type::Reference(reference) => formats_with_debug(&reference.elem),
+        Type::Group(group) => formats_with_debug(&group.elem),
+        Type::Paren(paren) => formats_with_debug(&paren.elem),
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+            matches!(
+                segment.ident.to_string().as_str(),
+                "Vec" | "VecDeque" | "LinkedList" | "Option" | "Result" | "HashMap" | "BTreeMap" | "HashSet"
+                    | "BTreeSet" | "BinaryHeap"
+            )
+        }),
+        _ => false,
+    }
+}
+
+/// Render a type the way it would be written in source, e.g. `Vec<String>`
+fn type_name(ty: &Type) -> String {
+    quote!(#ty)
+        .to_string()
+        .replace(" < ", "<")
+        .replace("< ", "<")
+        .replace(" <", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace("& ", "&")
+        .replace(" ;", ";")
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "KeyPathSchema cannot be derived for generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "KeyPathSchema can only be derived for structs",
+            ))
+        }
+    };
+
+    let krate = quote!(::rust_prelude_plus);
+    let mut field_names = Vec::new();
+    let mut field_types = Vec::new();
+    let mut entries = Vec::new();
+
+    let members: Vec<(Member, &syn::Field)> = match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .map(|field| (Member::Named(field.ident.clone().expect("named field")), field))
+            .collect(),
+        Fields::Unnamed(unnamed) => unnamed
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, field)| (Member::Unnamed(index.into()), field))
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    for (member, field) in members {
+        let options = field_options(field)?;
+        if options.skip {
+            continue;
+        }
+        let field_name = options.rename.clone().unwrap_or_else(|| match &member {
+            Member::Named(ident) => ident.to_string().trim_start_matches("r#").to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        });
+        let ty = &field.ty;
+        let ty_name = type_name(ty);
+        let keypath = quote! {
+            #krate::__private::KeyPaths::readable(|root: &#name| &root.#member)
+        };
+
+        let entry = if options.nested {
+            quote! {
+                fields.extend(
+                    <#ty as #krate::schema::KeyPathSchema>::schema()
+                        .into_iter()
+                        .map(|field| field.nested(#field_name, #keypath)),
+                );
+            }
+        } else {
+            let erased = if options.serialize {
+                quote!(#krate::dynamic::AnyKeyPath::serializable(#keypath))
+            } else if options.debug || formats_with_debug(ty) {
+                quote!(#krate::dynamic::AnyKeyPath::from_debug(#keypath))
+            } else {
+                quote!(#krate::dynamic::AnyKeyPath::new(#keypath))
+            };
+            quote! {
+                fields.push(#krate::schema::FieldSchema::new(#field_name, #ty_name, #erased));
+            }
+        };
+
+        field_names.push(field_name);
+        field_types.push(ty_name);
+        entries.push(entry);
+    }
+
+    Ok(quote! {
+        impl #krate::schema::KeyPathSchema for #name {
+            const FIELD_NAMES: &'static [&'static str] = &[#(#field_names),*];
+            const FIELD_TYPES: &'static [&'static str] = &[#(#field_types),*];
+
+            fn schema() -> ::std::vec::Vec<#krate::schema::FieldSchema<Self>> {
+                let mut fields = ::std::vec::Vec::new();
+                #(#entries)*
+                fields
+            }
+        }
+    })
+}
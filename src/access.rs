@@ -0,0 +1,669 @@
+//! Accessors that operations read values through
+//!
+//! Most operations take a `KeyPaths<T, V>` and pass `&V` to their closures. They accept any
+//! `KeyPathAccess<T>` instead, which also covers a `ZippedKeyPath` reading two keypaths at once:
+//! its closures receive a pair of references, so a predicate, group key or sort key can span
+//! several fields without cloning them. A `ComputedKeyPath` derives a value from the whole root
+//! with a closure and hands it to closures by value. A `CachedKeyPath` reads each value of one
+//! collection at most once, for operations that go over the same values several times.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use rust_prelude_plus::prelude::*;
+//! use key_paths_derive::Keypath;
+//!
+//! #[derive(Keypath, Debug, Clone)]
+//! struct Employee {
+//!     name: String,
+//!     department: String,
+//!     level: u8,
+//! }
+//!
+//! let mut employees = vec![
+//!     Employee { name: "Alice".to_string(), department: "Sales".to_string(), level: 2 },
+//!     Employee { name: "Bob".to_string(), department: "Engineering".to_string(), level: 3 },
+//!     Employee { name: "Carol".to_string(), department: "Engineering".to_string(), level: 1 },
+//! ];
+//!
+//! // Sort by department, then by level
+//! sort_by_keypath(&mut employees, Employee::department().zip(Employee::level()), |a, b| a.cmp(&b)).unwrap();
+//! let names: Vec<&str> = employees.iter().map(|employee| employee.name.as_str()).collect();
+//! assert_eq!(names, vec!["Carol", "Bob", "Alice"]);
+//!
+//! // A predicate over two fields
+//! let senior_engineers = filter_by_keypath(
+//!     employees,
+//!     Employee::department().zip(Employee::level()),
+//!     |(department, &level)| department == "Engineering" && level >= 3,
+//! ).unwrap();
+//! assert_eq!(senior_engineers[0].name, "Bob");
+//! ```
+
+use crate::error::{KeyPathError, KeyPathResult};
+use key_paths_core::KeyPaths;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+mod sealed {
+    pub trait Sealed: Sized {}
+    pub struct Bounds<T>(T);
+    impl<T> Sealed for Bounds<T> {}
+}
+
+use sealed::{Bounds, Sealed};
+
+/// The value an accessor hands to closures while `Self` and the root are borrowed for `'a`
+///
+/// This is split out of `KeyPathAccess` so operations can name the output for every `'a`
+/// without requiring `'static` roots. The defaulted parameter only carries the implied bounds
+/// `Self: 'a` and `Root: 'a` and is never written out.
+pub trait KeyPathAccessOutput<'a, Root, ImplicitBounds: Sealed = Bounds<(&'a Self, &'a Root)>> {
+    /// What operations pass to their closures
+    type Output;
+}
+
+/// Output of accessor `K` on `Root` for the borrow `'a`
+pub type AccessOutput<'a, K, Root> = <K as KeyPathAccessOutput<'a, Root>>::Output;
+
+/// Anything operations can read a value from a root through
+///
+/// Implemented for `KeyPaths<T, V>`, whose output is `&V`, and for `ZippedKeyPath`, whose output
+/// is a tuple of its parts' outputs. A reference to an accessor is an accessor too, so one can be
+/// lent to several operations.
+pub trait KeyPathAccess<Root>: for<'a> KeyPathAccessOutput<'a, Root> {
+    /// Owned form of the value, used where operations keep values such as group keys
+    type Value;
+
+    /// Read the value, or `None` if it is absent for this root
+    fn access<'a>(&'a self, root: &'a Root) -> Option<AccessOutput<'a, Self, Root>>;
+
+    /// Read the value, failing for roots this accessor cannot read at all
+    ///
+    /// `None` still means the value is absent, as with `access`. Accessors bound to one
+    /// collection, such as `CachedKeyPath`, return `KeyPathError::InvalidAccess` for any other
+    /// root, so operations that skip absent values do not skip those silently.
+    fn try_access<'a>(&'a self, root: &'a Root) -> KeyPathResult<Option<AccessOutput<'a, Self, Root>>> {
+        Ok(self.access(root))
+    }
+}
+
+/// Accessors that can produce an owned `Value`
+pub trait OwnedKeyPathAccess<Root>: KeyPathAccess<Root> {
+    /// Read the value as an owned `Value`
+    fn access_owned(&self, root: &Root) -> Option<Self::Value>;
+
+    /// Read the value as an owned `Value`, failing like `try_access` for foreign roots
+    fn try_access_owned(&self, root: &Root) -> KeyPathResult<Option<Self::Value>> {
+        Ok(self.access_owned(root))
+    }
+}
+
+impl<'a, T, V> KeyPathAccessOutput<'a, T> for KeyPaths<T, V> {
+    type Output = &'a V;
+}
+
+impl<T, V> KeyPathAccess<T> for KeyPaths<T, V> {
+    type Value = V;
+
+    fn access<'a>(&'a self, root: &'a T) -> Option<&'a V> {
+        self.get(root)
+    }
+}
+
+impl<T, V: Clone> OwnedKeyPathAccess<T> for KeyPaths<T, V> {
+    fn access_owned(&self, root: &T) -> Option<V> {
+        self.get(root).cloned()
+    }
+}
+
+impl<'a, T, K: KeyPathAccessOutput<'a, T>> KeyPathAccessOutput<'a, T> for &K {
+    type Output = AccessOutput<'a, K, T>;
+}
+
+impl<T, K: KeyPathAccess<T>> KeyPathAccess<T> for &K {
+    type Value = K::Value;
+
+    fn access<'a>(&'a self, root: &'a T) -> Option<AccessOutput<'a, K, T>> {
+        (**self).access(root)
+    }
+
+    fn try_access<'a>(&'a self, root: &'a T) -> KeyPathResult<Option<AccessOutput<'a, K, T>>> {
+        (**self).try_access(root)
+    }
+}
+
+impl<T, K: OwnedKeyPathAccess<T>> OwnedKeyPathAccess<T> for &K {
+    fn access_owned(&self, root: &T) -> Option<K::Value> {
+        (**self).access_owned(root)
+    }
+
+    fn try_access_owned(&self, root: &T) -> KeyPathResult<Option<K::Value>> {
+        (**self).try_access_owned(root)
+    }
+}
+
+/// Two accessors on the same root read together as a tuple
+///
+/// The output is the pair of both outputs, e.g. `(&V1, &V2)` for two keypaths, and the zipped
+/// value is absent when either part is. Zipping a `ZippedKeyPath` again nests the tuple.
+pub struct ZippedKeyPath<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Clone, B: Clone> Clone for ZippedKeyPath<A, B> {
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: self.second.clone(),
+        }
+    }
+}
+
+impl<A, B> std::fmt::Debug for ZippedKeyPath<A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZippedKeyPath")
+            .field("first", &std::any::type_name::<A>())
+            .field("second", &std::any::type_name::<B>())
+            .finish()
+    }
+}
+
+impl<A, B> ZippedKeyPath<A, B> {
+    /// Zip two accessors
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// The first accessor
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// The second accessor
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Add a third accessor, giving `((first, second), third)`
+    pub fn zip<C>(self, third: C) -> ZippedKeyPath<Self, C> {
+        ZippedKeyPath::new(self, third)
+    }
+
+    /// Read both values from `root`
+    pub fn get<'a, T>(&'a self, root: &'a T) -> Option<AccessOutput<'a, Self, T>>
+    where
+        Self: KeyPathAccess<T>,
+    {
+        self.access(root)
+    }
+}
+
+impl<'a, T, A, B> KeyPathAccessOutput<'a, T> for ZippedKeyPath<A, B>
+where
+    A: KeyPathAccess<T>,
+    B: KeyPathAccess<T>,
+{
+    type Output = (AccessOutput<'a, A, T>, AccessOutput<'a, B, T>);
+}
+
+impl<T, A, B> KeyPathAccess<T> for ZippedKeyPath<A, B>
+where
+    A: KeyPathAccess<T>,
+    B: KeyPathAccess<T>,
+{
+    type Value = (A::Value, B::Value);
+
+    fn access<'a>(&'a self, root: &'a T) -> Option<AccessOutput<'a, Self, T>> {
+        Some((self.first.access(root)?, self.second.access(root)?))
+    }
+
+    fn try_access<'a>(&'a self, root: &'a T) -> KeyPathResult<Option<AccessOutput<'a, Self, T>>> {
+        let first = self.first.try_access(root)?;
+        let second = self.second.try_access(root)?;
+        Ok(first.zip(second))
+    }
+}
+
+impl<T, A, B> OwnedKeyPathAccess<T> for ZippedKeyPath<A, B>
+where
+    A: OwnedKeyPathAccess<T>,
+    B: OwnedKeyPathAccess<T>,
+{
+    fn access_owned(&self, root: &T) -> Option<Self::Value> {
+        Some((self.first.access_owned(root)?, self.second.access_owned(root)?))
+    }
+
+    fn try_access_owned(&self, root: &T) -> KeyPathResult<Option<Self::Value>> {
+        let first = self.first.try_access_owned(root)?;
+        let second = self.second.try_access_owned(root)?;
+        Ok(first.zip(second))
+    }
+}
+
+/// Zip two keypaths on the same root into one yielding both values
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Sale {
+///     region: String,
+///     year: u16,
+///     amount: f64,
+/// }
+///
+/// let sales = vec![
+///     Sale { region: "EU".to_string(), year: 2024, amount: 10.0 },
+///     Sale { region: "EU".to_string(), year: 2025, amount: 20.0 },
+///     Sale { region: "EU".to_string(), year: 2024, amount: 5.0 },
+/// ];
+///
+/// let by_region_year = group_by_keypath(&sales, zip_keypaths(Sale::region(), Sale::year()), |(region, &year)| {
+///     (region.clone(), year)
+/// }).unwrap();
+/// assert_eq!(by_region_year[&("EU".to_string(), 2024)].len(), 2);
+/// ```
+pub fn zip_keypaths<A, B>(first: A, second: B) -> ZippedKeyPath<A, B> {
+    ZippedKeyPath::new(first, second)
+}
+
+/// Zip a keypath with another accessor on the same root
+pub trait KeyPathsZipExt<T, V>: Sized {
+    /// Read this keypath and `other` together as a tuple
+    fn zip<B: KeyPathAccess<T>>(self, other: B) -> ZippedKeyPath<Self, B>;
+}
+
+impl<T, V> KeyPathsZipExt<T, V> for KeyPaths<T, V> {
+    fn zip<B: KeyPathAccess<T>>(self, other: B) -> ZippedKeyPath<Self, B> {
+        ZippedKeyPath::new(self, other)
+    }
+}
+
+/// A value derived from the whole root by a closure, such as a full name or an order total
+///
+/// Operations pass the computed `V` to their closures by value. The closure is shared behind an
+/// `Arc` and must be `Send + Sync`, so computed keypaths also work with the parallel operations.
+pub struct ComputedKeyPath<T, V> {
+    compute: Arc<dyn Fn(&T) -> V + Send + Sync>,
+    cache: Option<Arc<dyn MemoCache>>,
+}
+
+impl<T, V> Clone for ComputedKeyPath<T, V> {
+    fn clone(&self) -> Self {
+        Self {
+            compute: self.compute.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<T, V> std::fmt::Debug for ComputedKeyPath<T, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComputedKeyPath")
+            .field("root", &std::any::type_name::<T>())
+            .field("value", &std::any::type_name::<V>())
+            .field("memoized", &self.cache.is_some())
+            .finish()
+    }
+}
+
+/// Type-erased handle to the cache behind `ComputedKeyPath::memoized_by`
+trait MemoCache: Send + Sync {
+    fn len(&self) -> usize;
+    fn clear(&self);
+}
+
+/// Values cached by key, holding at most `capacity` of them
+struct Memo<Q, V> {
+    entries: Mutex<HashMap<Q, V>>,
+    capacity: usize,
+}
+
+impl<Q: Hash + Eq, V: Clone> Memo<Q, V> {
+    fn get(&self, key: &Q) -> Option<V> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).get(key).cloned()
+    }
+
+    fn insert(&self, key: Q, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            entries.clear();
+        }
+        entries.insert(key, value);
+    }
+}
+
+impl<Q: Send, V: Send> MemoCache for Memo<Q, V> {
+    fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    fn clear(&self) {
+        *self.entries.lock().unwrap_or_else(PoisonError::into_inner) = HashMap::new();
+    }
+}
+
+impl<T: 'static, V: 'static> ComputedKeyPath<T, V> {
+    /// Create a computed keypath from a closure
+    pub fn new(compute: impl Fn(&T) -> V + Send + Sync + 'static) -> Self {
+        Self {
+            compute: Arc::new(compute),
+            cache: None,
+        }
+    }
+
+    /// Compute the value for `root`
+    pub fn compute(&self, root: &T) -> V {
+        (self.compute)(root)
+    }
+
+    /// Whether computed values are cached
+    pub fn is_memoized(&self) -> bool {
+        self.cache.is_some()
+    }
+
+    /// Cache up to `capacity` computed values under a cheap key derived from each root, such as an id
+    ///
+    /// Roots with equal keys are assumed to compute equal values. The cache is shared by clones of
+    /// the returned keypath and dropped with the last of them; when it is full it is emptied
+    /// before the next value is stored, so it never holds more than `capacity` values. Call
+    /// `clear_cache` when the roots change. The closure runs outside the cache lock, so concurrent
+    /// callers may compute the same value more than once. To cache the values of one collection
+    /// by position instead, use `cache_keypath`.
+    pub fn memoized_by<Q, F>(self, capacity: usize, key: F) -> Self
+    where
+        Q: Hash + Eq + Send + 'static,
+        V: Clone + Send,
+        F: Fn(&T) -> Q + Send + Sync + 'static,
+    {
+        let compute = self.compute;
+        let memo = Arc::new(Memo {
+            entries: Mutex::new(HashMap::new()),
+            capacity,
+        });
+        let cache = Arc::clone(&memo);
+        Self {
+            compute: Arc::new(move |root| {
+                let key = key(root);
+                if let Some(value) = memo.get(&key) {
+                    return value;
+                }
+                let value = compute(root);
+                memo.insert(key, value.clone());
+                value
+            }),
+            cache: Some(cache),
+        }
+    }
+
+    /// Number of values currently cached, `0` unless memoized
+    pub fn cached_count(&self) -> usize {
+        self.cache.as_ref().map_or(0, |cache| cache.len())
+    }
+
+    /// Drop every cached value and release the cache's memory
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Read this computed value and `other` together as a tuple
+    pub fn zip<B: KeyPathAccess<T>>(self, other: B) -> ZippedKeyPath<Self, B> {
+        ZippedKeyPath::new(self, other)
+    }
+}
+
+impl<'a, T, V> KeyPathAccessOutput<'a, T> for ComputedKeyPath<T, V> {
+    type Output = V;
+}
+
+impl<T, V> KeyPathAccess<T> for ComputedKeyPath<T, V> {
+    type Value = V;
+
+    fn access<'a>(&'a self, root: &'a T) -> Option<V> {
+        Some((self.compute)(root))
+    }
+}
+
+impl<T, V> OwnedKeyPathAccess<T> for ComputedKeyPath<T, V> {
+    fn access_owned(&self, root: &T) -> Option<V> {
+        Some((self.compute)(root))
+    }
+}
+
+/// A keypath to a value computed from the root, usable wherever operations read a keypath
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Line {
+///     sku: String,
+///     quantity: u32,
+///     price: f64,
+/// }
+///
+/// let lines = vec![
+///     Line { sku: "A".to_string(), quantity: 3, price: 2.5 },
+///     Line { sku: "B".to_string(), quantity: 1, price: 20.0 },
+///     Line { sku: "C".to_string(), quantity: 10, price: 0.5 },
+/// ];
+///
+/// let total = computed_keypath(|line: &Line| line.quantity as f64 * line.price);
+///
+/// let large = filter_by_keypath(lines.clone(), total.clone(), |total| total > 6.0).unwrap();
+/// assert_eq!(large.len(), 2);
+///
+/// let revenue = fold_keypath(lines, total, 0.0, |acc, total| acc + total).unwrap();
+/// assert_eq!(revenue, 32.5);
+/// ```
+pub fn computed_keypath<T: 'static, V: 'static>(compute: impl Fn(&T) -> V + Send + Sync + 'static) -> ComputedKeyPath<T, V> {
+    ComputedKeyPath::new(compute)
+}
+
+/// Keypath values of one collection, each read at most once and kept by element index
+///
+/// For expensive keypaths read in several passes, such as `window_by_keypath` followed by
+/// `rolling_by_keypath`, or a group followed by an aggregate over each group. Lend `&cached` to
+/// each operation, or call the index-based methods (`try_get`, `window`, `group_indices` and
+/// `fold_indices`) directly.
+///
+/// Values are kept by element index. Lent to an operation, a root is matched to its index by its
+/// address, so only the collection's own elements, directly or through references to them, can
+/// be read. Any other root, including a clone of an element, is rejected with
+/// `KeyPathError::InvalidAccess` by `try_access`; `access` reports it as absent.
+pub struct CachedKeyPath<'c, T, K: OwnedKeyPathAccess<T>> {
+    items: &'c [T],
+    keypath: K,
+    values: Vec<OnceLock<Option<K::Value>>>,
+}
+
+impl<T, K: OwnedKeyPathAccess<T>> std::fmt::Debug for CachedKeyPath<'_, T, K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedKeyPath")
+            .field("keypath", &std::any::type_name::<K>())
+            .field("len", &self.items.len())
+            .field("cached", &self.cached_count())
+            .finish()
+    }
+}
+
+impl<'c, T, K: OwnedKeyPathAccess<T>> CachedKeyPath<'c, T, K> {
+    /// Cache the values of `keypath` for the elements of `items`, reading them on first use
+    pub fn new(items: &'c [T], keypath: K) -> Self {
+        Self {
+            items,
+            keypath,
+            values: std::iter::repeat_with(OnceLock::new).take(items.len()).collect(),
+        }
+    }
+
+    /// The value of the element at `index`, read on first use
+    pub fn get(&self, index: usize) -> Option<&K::Value> {
+        let item = self.items.get(index)?;
+        self.values[index]
+            .get_or_init(|| self.keypath.access_owned(item))
+            .as_ref()
+    }
+
+    /// The value of the element at `index`, failing if there is no such element or its value is absent
+    pub fn try_get(&self, index: usize) -> KeyPathResult<&K::Value> {
+        self.get(index).ok_or_else(|| KeyPathError::InvalidAccess {
+            message: format!("KeyPath access failed for cached element {}", index),
+        })
+    }
+
+    /// Apply `f` to every window of `window_size` consecutive values, like `window_by_keypath`
+    pub fn window<F, O>(&self, window_size: usize, f: F) -> KeyPathResult<Vec<O>>
+    where
+        F: Fn(&[&K::Value]) -> O,
+    {
+        if window_size == 0 || window_size > self.items.len() {
+            return Err(KeyPathError::CollectionError {
+                message: format!("Invalid window size: {}", window_size),
+            });
+        }
+        let values = (0..self.items.len())
+            .map(|index| self.try_get(index))
+            .collect::<KeyPathResult<Vec<_>>>()?;
+        Ok(values.windows(window_size).map(f).collect())
+    }
+
+    /// Indices of the elements grouped by another accessor, in order within each group
+    ///
+    /// Pair with `fold_indices` to aggregate each group from the cache.
+    pub fn group_indices<A, F, G>(&self, keypath: A, key_fn: F) -> KeyPathResult<HashMap<G, Vec<usize>>>
+    where
+        A: KeyPathAccess<T>,
+        F: for<'a> Fn(AccessOutput<'a, A, T>) -> G,
+        G: Hash + Eq,
+    {
+        let mut groups: HashMap<G, Vec<usize>> = HashMap::new();
+        for (index, item) in self.items.iter().enumerate() {
+            let value = keypath.access(item).ok_or_else(|| KeyPathError::InvalidAccess {
+                message: "KeyPath access failed in group_indices".to_string(),
+            })?;
+            groups.entry(key_fn(value)).or_default().push(index);
+        }
+        Ok(groups)
+    }
+
+    /// Fold the cached values of the elements at `indices`
+    pub fn fold_indices<I, F, B>(&self, indices: I, init: B, mut f: F) -> KeyPathResult<B>
+    where
+        I: IntoIterator<Item = usize>,
+        F: FnMut(B, &K::Value) -> B,
+    {
+        let mut acc = init;
+        for index in indices {
+            acc = f(acc, self.try_get(index)?);
+        }
+        Ok(acc)
+    }
+
+    /// The cached collection
+    pub fn items(&self) -> &'c [T] {
+        self.items
+    }
+
+    /// Number of elements whose value has been read so far
+    pub fn cached_count(&self) -> usize {
+        self.values.iter().filter(|value| value.get().is_some()).count()
+    }
+
+    /// Index of `root` in the cached collection, if it is one of its elements
+    fn index_of(&self, root: &T) -> Option<usize> {
+        let offset = (root as *const T as usize).checked_sub(self.items.as_ptr() as usize)?;
+        let index = offset.checked_div(std::mem::size_of::<T>()).unwrap_or(0);
+        self.items
+            .get(index)
+            .filter(|item| std::ptr::eq(*item, root))
+            .map(|_| index)
+    }
+}
+
+impl<'a, T, K: OwnedKeyPathAccess<T>> KeyPathAccessOutput<'a, T> for CachedKeyPath<'_, T, K> {
+    type Output = &'a K::Value;
+}
+
+impl<T, K: OwnedKeyPathAccess<T>> KeyPathAccess<T> for CachedKeyPath<'_, T, K> {
+    type Value = K::Value;
+
+    fn access<'a>(&'a self, root: &'a T) -> Option<&'a K::Value> {
+        self.get(self.index_of(root)?)
+    }
+
+    fn try_access<'a>(&'a self, root: &'a T) -> KeyPathResult<Option<&'a K::Value>> {
+        let index = self.index_of(root).ok_or_else(|| KeyPathError::InvalidAccess {
+            message: "Root is not an element of the cached collection".to_string(),
+        })?;
+        Ok(self.get(index))
+    }
+}
+
+impl<T, K> OwnedKeyPathAccess<T> for CachedKeyPath<'_, T, K>
+where
+    K: OwnedKeyPathAccess<T>,
+    K::Value: Clone,
+{
+    fn access_owned(&self, root: &T) -> Option<K::Value> {
+        self.access(root).cloned()
+    }
+
+    fn try_access_owned(&self, root: &T) -> KeyPathResult<Option<K::Value>> {
+        Ok(self.try_access(root)?.cloned())
+    }
+}
+
+/// Cache the values of a keypath for one collection, so multi-pass operations read each once
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Reading {
+///     sensor: String,
+///     raw: u32,
+/// }
+///
+/// let readings = vec![
+///     Reading { sensor: "a".to_string(), raw: 10 },
+///     Reading { sensor: "b".to_string(), raw: 20 },
+///     Reading { sensor: "a".to_string(), raw: 30 },
+/// ];
+///
+/// // Imagine a costly calibration
+/// let calibrated = cache_keypath(&readings, computed_keypath(|reading: &Reading| reading.raw as f64 * 1.5));
+///
+/// let averages = readings.window_by_keypath(&calibrated, 2, |pair| (pair[0] + pair[1]) / 2.0).unwrap();
+/// assert_eq!(averages, vec![22.5, 37.5]);
+///
+/// // Group element indices, then aggregate each group from the same cache
+/// let groups = calibrated.group_indices(Reading::sensor(), |sensor| sensor.clone()).unwrap();
+/// let total_a = calibrated.fold_indices(groups["a"].iter().copied(), 0.0, |acc, &value| acc + value).unwrap();
+/// assert_eq!(total_a, 60.0);
+///
+/// // A root that is not one of the cached elements is an error, not an absent value
+/// let copy = readings[0].clone();
+/// assert!(calibrated.try_access(&copy).is_err());
+/// assert_eq!(calibrated.cached_count(), 3);
+/// ```
+pub fn cache_keypath<T, K: OwnedKeyPathAccess<T>>(items: &[T], keypath: K) -> CachedKeyPath<'_, T, K> {
+    CachedKeyPath::new(items, keypath)
+}
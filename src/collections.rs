@@ -1,124 +1,805 @@
 //! Collection operations for keypath-based functional programming
+//!
+//! `KeyPathsCollectionExt` works on anything that can be iterated by reference (`Vec<T>`,
+//! `[T]`, `[T; N]`, `Box<[T]>`, `VecDeque<T>`, `HashSet<T>`, `BTreeSet<T>`, ...). Its sorting and
+//! in-place mutation methods also need `KeyPathsCollectionMutExt`, implemented for the
+//! collections that can be reordered in place: `[T]`, `Vec<T>`, `[T; N]`, `Box<[T]>` and
+//! `VecDeque<T>`.
 
 use key_paths_core::KeyPaths;
+use crate::access::{AccessOutput, KeyPathAccess, KeyPathAccessOutput, OwnedKeyPathAccess};
 use crate::error::{KeyPathResult, KeyPathError};
-use crate::traits::KeyPathsOperable;
-use std::collections::{HashMap, HashSet, BTreeMap};
+use crate::traits::{KeyPathRoot, KeyPathsOperable};
+use std::collections::{HashMap, HashSet, BTreeMap, VecDeque};
+
+/// Which occurrence `dedup_by_keypath` keeps when several elements share a keypath value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeepOccurrence {
+    /// Keep the first element with each value
+    #[default]
+    First,
+    /// Keep the last element with each value
+    Last,
+}
+
+/// How `histogram_by_keypath` splits the range of values into buckets
+///
+/// `EqualWidth` and `Quantiles` derive their edges from the finite values only; infinite and NaN
+/// values fall outside those edges and are not counted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binning {
+    /// The given number of equally wide buckets spanning the smallest to the largest value
+    EqualWidth(usize),
+    /// Buckets between consecutive edges, which must be strictly increasing
+    ///
+    /// Values outside the first and last edge are not counted.
+    Edges(Vec<f64>),
+    /// The given number of buckets holding roughly the same number of values each
+    ///
+    /// Edges that coincide because of repeated values are merged, so fewer buckets may be returned.
+    Quantiles(usize),
+}
+
+/// Numeric keypath values that `histogram_by_keypath` can bucket
+///
+/// Implemented for every primitive integer and float type. Wide integers such as `u64` or `i128`
+/// may lose precision in the conversion, which only matters for bucket boundaries.
+pub trait HistogramValue {
+    /// The value as an `f64`
+    fn to_f64(&self) -> f64;
+}
+
+macro_rules! impl_histogram_value {
+    ($($ty:ty),*) => {
+        $(
+            impl HistogramValue for $ty {
+                fn to_f64(&self) -> f64 {
+                    *self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_histogram_value!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+/// One bucket of a histogram
+///
+/// Buckets cover `lower..upper`, except the last one which also includes `upper`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramBucket {
+    /// Inclusive lower bound
+    pub lower: f64,
+    /// Upper bound, exclusive except for the last bucket
+    pub upper: f64,
+    /// Number of values in the bucket
+    pub count: usize,
+}
+
+/// A two-dimensional table produced by `pivot_by_keypaths` or `crosstab_by_keypaths`
+///
+/// Rows and columns appear in the order their keys were first seen. Cells with no elements
+/// hold the fill value; totals aggregate the underlying values of a whole row, column or table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PivotTable<RK, CK, A> {
+    rows: Vec<RK>,
+    columns: Vec<CK>,
+    cells: Vec<Vec<A>>,
+    row_totals: Vec<A>,
+    column_totals: Vec<A>,
+    grand_total: A,
+}
+
+impl<RK, CK, A> PivotTable<RK, CK, A> {
+    /// Row keys
+    pub fn rows(&self) -> &[RK] {
+        &self.rows
+    }
+
+    /// Column keys
+    pub fn columns(&self) -> &[CK] {
+        &self.columns
+    }
+
+    /// Cells, indexed by row then column
+    pub fn cells(&self) -> &[Vec<A>] {
+        &self.cells
+    }
+
+    /// Aggregate of every value in each row
+    pub fn row_totals(&self) -> &[A] {
+        &self.row_totals
+    }
+
+    /// Aggregate of every value in each column
+    pub fn column_totals(&self) -> &[A] {
+        &self.column_totals
+    }
+
+    /// Aggregate of every value in the table
+    pub fn grand_total(&self) -> &A {
+        &self.grand_total
+    }
+
+    /// Cell at the given row and column keys
+    pub fn get(&self, row: &RK, column: &CK) -> Option<&A>
+    where
+        RK: PartialEq,
+        CK: PartialEq,
+    {
+        let row = self.rows.iter().position(|key| key == row)?;
+        let column = self.columns.iter().position(|key| key == column)?;
+        Some(&self.cells[row][column])
+    }
+
+    /// Reorder rows and columns by their keys
+    pub fn sorted(mut self) -> Self
+    where
+        RK: Ord,
+        CK: Ord,
+    {
+        let mut row_order: Vec<usize> = (0..self.rows.len()).collect();
+        row_order.sort_by(|&a, &b| self.rows[a].cmp(&self.rows[b]));
+        let mut column_order: Vec<usize> = (0..self.columns.len()).collect();
+        column_order.sort_by(|&a, &b| self.columns[a].cmp(&self.columns[b]));
+
+        self.rows = reorder(self.rows, &row_order);
+        self.row_totals = reorder(self.row_totals, &row_order);
+        self.columns = reorder(self.columns, &column_order);
+        self.column_totals = reorder(self.column_totals, &column_order);
+        self.cells = reorder(self.cells, &row_order)
+            .into_iter()
+            .map(|row| reorder(row, &column_order))
+            .collect();
+        self
+    }
+}
+
+/// Per-keypath failure counts for a collection
+///
+/// Built with `check`, which tries a keypath against every element, or `record`, which takes the
+/// errors returned by `map_keypath_collect_errors` and similar operations. Keypaths are listed in
+/// the order they were first added.
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Contact {
+///     name: String,
+///     email: Option<String>,
+///     phone: Option<String>,
+/// }
+///
+/// let contacts = vec![
+///     Contact { name: "Alice".to_string(), email: Some("alice@example.com".to_string()), phone: None },
+///     Contact { name: "Bob".to_string(), email: None, phone: None },
+/// ];
+///
+/// let report = KeyPathReport::new(contacts.len())
+///     .check("email", &contacts, Contact::email())
+///     .check("phone", &contacts, Contact::phone());
+///
+/// assert_eq!(report.failures("email"), 1);
+/// assert_eq!(report.failed_indices("phone"), &[0, 1]);
+/// assert_eq!(report.failed_elements(), 2);
+/// assert_eq!(report.to_string(), "2 of 2 elements failed: email 1, phone 2");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyPathReport {
+    total: usize,
+    keypaths: Vec<(String, Vec<usize>)>,
+}
+
+impl KeyPathReport {
+    /// An empty report for a collection of `total` elements
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            keypaths: Vec::new(),
+        }
+    }
+
+    /// Count the elements of `collection` where `keypath` cannot be read
+    pub fn check<I, T, K>(mut self, name: impl Into<String>, collection: &[I], keypath: K) -> Self
+    where
+        I: KeyPathRoot<T>,
+        K: KeyPathAccess<T>,
+    {
+        let failed: Vec<usize> = collection
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| keypath.access((*item).keypath_root()).is_none())
+            .map(|(index, _)| index)
+            .collect();
+        self.add(name.into(), failed);
+        self
+    }
+
+    /// Add the errors returned by a `*_collect_errors` operation under `name`
+    pub fn record(&mut self, name: impl Into<String>, errors: &[(usize, KeyPathError)]) {
+        self.add(name.into(), errors.iter().map(|(index, _)| *index));
+    }
+
+    fn add(&mut self, name: String, failed: impl IntoIterator<Item = usize>) {
+        let position = match self.keypaths.iter().position(|(existing, _)| *existing == name) {
+            Some(position) => position,
+            None => {
+                self.keypaths.push((name, Vec::new()));
+                self.keypaths.len() - 1
+            }
+        };
+        let indices = &mut self.keypaths[position].1;
+        indices.extend(failed);
+        indices.sort_unstable();
+        indices.dedup();
+    }
+
+    /// Number of elements in the collection
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Number of elements that failed for the named keypath
+    pub fn failures(&self, name: &str) -> usize {
+        self.failed_indices(name).len()
+    }
+
+    /// Indices of the elements that failed for the named keypath, in ascending order
+    pub fn failed_indices(&self, name: &str) -> &[usize] {
+        self.keypaths
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, indices)| indices.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Failure count of every keypath
+    pub fn failure_counts(&self) -> Vec<(&str, usize)> {
+        self.keypaths
+            .iter()
+            .map(|(name, indices)| (name.as_str(), indices.len()))
+            .collect()
+    }
+
+    /// Number of elements that failed for at least one keypath
+    pub fn failed_elements(&self) -> usize {
+        self.keypaths
+            .iter()
+            .flat_map(|(_, indices)| indices.iter())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Whether every keypath succeeded for every element
+    pub fn is_clean(&self) -> bool {
+        self.keypaths.iter().all(|(_, indices)| indices.is_empty())
+    }
+}
+
+impl std::fmt::Display for KeyPathReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} of {} elements failed", self.failed_elements(), self.total)?;
+        for (i, (name, indices)) in self.keypaths.iter().enumerate() {
+            write!(f, "{} {} {}", if i == 0 { ":" } else { "," }, name, indices.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Move `items` into the order given by a permutation of their indices
+fn reorder<X>(items: Vec<X>, order: &[usize]) -> Vec<X> {
+    let mut slots: Vec<Option<X>> = items.into_iter().map(Some).collect();
+    order.iter().filter_map(|&index| slots[index].take()).collect()
+}
+
+/// Compute the bucket edges for a set of finite values
+fn bin_edges(binning: &Binning, values: &[f64]) -> KeyPathResult<Vec<f64>> {
+    let invalid = |message: &str| KeyPathError::CollectionError { message: message.to_string() };
+    match binning {
+        Binning::EqualWidth(0) | Binning::Quantiles(0) => Err(invalid("Bin count must be greater than 0")),
+        Binning::Edges(edges) => {
+            if edges.len() < 2 {
+                return Err(invalid("At least two bin edges are required"));
+            }
+            if edges.windows(2).any(|pair| pair[0].partial_cmp(&pair[1]) != Some(std::cmp::Ordering::Less)) {
+                return Err(invalid("Bin edges must be strictly increasing"));
+            }
+            Ok(edges.clone())
+        }
+        _ if values.is_empty() => Ok(Vec::new()),
+        Binning::EqualWidth(count) => {
+            let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            // A single distinct value still gets a bucket of non-zero width around it
+            let (min, max) = if min == max { (min - 0.5, max + 0.5) } else { (min, max) };
+            let width = (max - min) / *count as f64;
+            Ok((0..=*count)
+                .map(|i| if i == *count { max } else { min + width * i as f64 })
+                .collect())
+        }
+        Binning::Quantiles(count) => {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            let last = (sorted.len() - 1) as f64;
+            let mut edges: Vec<f64> = (0..=*count)
+                .map(|i| {
+                    let position = last * i as f64 / *count as f64;
+                    let below = sorted[position.floor() as usize];
+                    let above = sorted[position.ceil() as usize];
+                    below + (above - below) * position.fract()
+                })
+                .collect();
+            edges.dedup();
+            if edges.len() == 1 {
+                edges = vec![edges[0] - 0.5, edges[0] + 0.5];
+            }
+            Ok(edges)
+        }
+    }
+}
+
+/// Index of the bucket holding `value`, if it falls within the edges
+fn bucket_index(edges: &[f64], value: f64) -> Option<usize> {
+    let last = *edges.last()?;
+    if value.is_nan() || value < edges[0] || value > last {
+        return None;
+    }
+    let buckets = edges.len().checked_sub(1)?;
+    if value == last {
+        return buckets.checked_sub(1);
+    }
+    edges
+        .partition_point(|&edge| edge <= value)
+        .checked_sub(1)
+        .filter(|&index| index < buckets)
+}
 
 /// Extension trait for collections with keypath operations
+///
+/// Implemented for every collection whose shared reference can be iterated over `&T`.
+/// `sort_by_keypath`, `for_each_keypath_mut` and `update_where` are only available when the
+/// collection also implements `KeyPathsCollectionMutExt`.
 pub trait KeyPathsCollectionExt<T> {
     /// Extract values from keypaths into collections
-    fn collect_keypath<V>(&self, keypath: KeyPaths<T, V>) -> KeyPathResult<Vec<V>>
+    fn collect_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<K::Value>>
     where
-        V: Clone;
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>;
     
     /// Partition elements by keypath predicate
-    fn partition_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<(Vec<T>, Vec<T>)>
+    fn partition_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<(Vec<T>, Vec<T>)>
     where
+        T: KeyPathRoot<R>,
         T: Clone,
-        F: Fn(&V) -> bool;
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool;
     
     /// Group elements by keypath values
-    fn group_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, f: F) -> KeyPathResult<HashMap<V, Vec<T>>>
+    fn group_by_keypath<R, K, F>(&self, keypath: K, f: F) -> KeyPathResult<HashMap<K::Value, Vec<T>>>
     where
-        V: std::hash::Hash + Eq + Clone,
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq + Clone,
         T: Clone,
-        F: Fn(&V) -> V;
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> K::Value;
     
     /// Sort elements by keypath values
-    fn sort_by_keypath<V, F>(&mut self, keypath: KeyPaths<T, V>, compare: F) -> KeyPathResult<()>
+    ///
+    /// Needs a collection that can be reordered in place, see `KeyPathsCollectionMutExt`.
+    fn sort_by_keypath<R, K, F>(&mut self, keypath: K, compare: F) -> KeyPathResult<()>
     where
-        F: Fn(&V, &V) -> std::cmp::Ordering;
+        Self: KeyPathsCollectionMutExt<T>,
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, AccessOutput<'a, K, R>) -> std::cmp::Ordering,
+    {
+        self.keypath_elements_mut().sort_by(|a, b| {
+            let a_val = keypath.access(a.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in sort_by_keypath")
+            });
+            let b_val = keypath.access(b.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in sort_by_keypath")
+            });
+            compare(a_val, b_val)
+        });
+        Ok(())
+    }
     
     /// Find elements matching keypath conditions
-    fn find_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<Option<&T>>
+    fn find_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<Option<&T>>
     where
-        F: Fn(&V) -> bool;
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool;
     
     /// Check if any element matches keypath condition
-    fn any_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<bool>
+    fn any_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<bool>
     where
-        F: Fn(&V) -> bool;
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool;
     
     /// Check if all elements match keypath condition
-    fn all_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<bool>
+    fn all_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<bool>
     where
-        F: Fn(&V) -> bool;
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool;
     
     /// Count elements matching keypath condition
-    fn count_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<usize>
+    fn count_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<usize>
     where
-        F: Fn(&V) -> bool;
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool;
     
     /// Get unique values from keypath
-    fn unique_by_keypath<V>(&self, keypath: KeyPaths<T, V>) -> KeyPathResult<HashSet<V>>
+    fn unique_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<HashSet<K::Value>>
     where
-        V: std::hash::Hash + Eq + Clone;
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq;
     
     /// Get distinct values from keypath with counts
-    fn distinct_by_keypath<V>(&self, keypath: KeyPaths<T, V>) -> KeyPathResult<HashMap<V, usize>>
+    ///
+    /// See `value_counts_by_keypath` for the counts ordered by frequency.
+    fn distinct_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<HashMap<K::Value, usize>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq;
+    
+    /// Remove elements with a repeated keypath value, keeping the first or last occurrence
+    ///
+    /// The kept elements stay in their original relative order.
+    fn dedup_by_keypath<R, K>(&self, keypath: K, keep: KeepOccurrence) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R>,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq;
+    
+    /// Groups of elements sharing a keypath value, for every value seen more than once
+    ///
+    /// Groups are ordered by the first occurrence of their value.
+    fn duplicates_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<(K::Value, Vec<T>)>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq + Clone;
+    
+    /// Check that no two elements share a keypath value
+    fn is_unique_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<bool>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq;
+    
+    /// The `k` elements with the largest keypath values, largest first
+    ///
+    /// Uses a bounded heap, so this is O(n log k) rather than a full sort. Ties keep the earlier element.
+    /// Each value is read once and the comparison receives references to the values.
+    fn top_k_by_keypath<R, K, F>(&self, keypath: K, k: usize, compare: F) -> KeyPathResult<Vec<&T>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering;
+    
+    /// The `k` elements with the smallest keypath values, smallest first
+    fn bottom_k_by_keypath<R, K, F>(&self, keypath: K, k: usize, compare: F) -> KeyPathResult<Vec<&T>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering;
+    
+    /// Values at a keypath with the number of elements holding each, most frequent first
+    ///
+    /// Values with the same count are ordered by first occurrence.
+    fn value_counts_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<(K::Value, usize)>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq + Clone;
+    
+    /// Count numeric keypath values per bucket
+    fn histogram_by_keypath<R, K>(&self, keypath: K, binning: Binning) -> KeyPathResult<Vec<HistogramBucket>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: HistogramValue;
+    
+    /// Bucket elements by a numeric keypath value, returning each bucket with its elements
+    fn histogram_elements_by_keypath<R, K>(
+        &self,
+        keypath: K,
+        binning: Binning,
+    ) -> KeyPathResult<Vec<(HistogramBucket, Vec<&T>)>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: HistogramValue;
+    
+    /// Aggregate values into a table with one row per `row_keypath` value and one column per
+    /// `column_keypath` value
+    ///
+    /// `agg` receives the values at `value_keypath` of the elements in a cell, row or column;
+    /// cells without elements hold `fill`.
+    fn pivot_by_keypaths<R, RK, CK, VK, A, F>(
+        &self,
+        row_keypath: RK,
+        column_keypath: CK,
+        value_keypath: VK,
+        agg: F,
+        fill: A,
+    ) -> KeyPathResult<PivotTable<RK::Value, CK::Value, A>>
+    where
+        T: KeyPathRoot<R>,
+        RK: OwnedKeyPathAccess<R>,
+        RK::Value: std::hash::Hash + Eq + Clone,
+        CK: OwnedKeyPathAccess<R>,
+        CK::Value: std::hash::Hash + Eq + Clone,
+        VK: KeyPathAccess<R>,
+        for<'a> AccessOutput<'a, VK, R>: Clone,
+        A: Clone,
+        F: for<'a> Fn(&[AccessOutput<'a, VK, R>]) -> A;
+    
+    /// Count elements per combination of `row_keypath` and `column_keypath` values
+    fn crosstab_by_keypaths<R, RK, CK>(
+        &self,
+        row_keypath: RK,
+        column_keypath: CK,
+    ) -> KeyPathResult<PivotTable<RK::Value, CK::Value, usize>>
+    where
+        T: KeyPathRoot<R>,
+        RK: OwnedKeyPathAccess<R> + Clone,
+        RK::Value: std::hash::Hash + Eq + Clone,
+        for<'a> AccessOutput<'a, RK, R>: Clone,
+        CK: OwnedKeyPathAccess<R>,
+        CK::Value: std::hash::Hash + Eq + Clone;
+    
+    /// Fold over keypath values, returning the accumulator after every element
+    fn scan_keypath<R, K, B, F>(&self, keypath: K, init: B, f: F) -> KeyPathResult<Vec<B>>
+    where
+        T: KeyPathRoot<R>,
+        B: Clone,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(B, AccessOutput<'a, K, R>) -> B;
+    
+    /// Running total of keypath values
+    fn cumulative_sum_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<K::Value>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: Clone + std::ops::Add<Output = K::Value>;
+    
+    /// Largest keypath value seen so far, for every element
+    fn cumulative_max_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<K::Value>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: Clone + PartialOrd;
+    
+    /// Number of elements matching a keypath predicate so far, for every element
+    fn running_count_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<Vec<usize>>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool;
+    
+    /// Difference between each keypath value and the previous one
+    ///
+    /// Returns one value per consecutive pair, so one fewer than the number of elements. The
+    /// subtraction is the value type's own `-`, so values must never decrease for unsigned types:
+    /// `[3u32, 1]` overflows, which panics in debug builds. Use `diff_by_keypath_with` for a
+    /// checked or signed difference.
+    fn diff_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<K::Value>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: Clone + std::ops::Sub<Output = K::Value>;
+    
+    /// Difference between each keypath value and the previous one, computed by `difference`
+    ///
+    /// `difference` receives the current value, then the previous one, e.g. `u32::checked_sub`
+    /// or `|current, previous| i64::from(current) - i64::from(previous)`.
+    fn diff_by_keypath_with<R, K, D, F>(&self, keypath: K, difference: F) -> KeyPathResult<Vec<D>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: Clone,
+        F: Fn(K::Value, K::Value) -> D;
+    
+    /// Elements of either collection, identified by keypath value
+    ///
+    /// Like the other set operations, each value appears at most once, represented by its first
+    /// element; elements from `self` come first, in their original order.
+    fn union_by_keypath<R, K>(&self, other: &[T], keypath: K) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R>,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq;
+    
+    /// Elements of `self` whose keypath value also occurs in `other`
+    fn intersect_by_keypath<R, K>(&self, other: &[T], keypath: K) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R> + Clone,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq;
+    
+    /// Elements of `self` whose keypath value does not occur in `other`
+    fn difference_by_keypath<R, K>(&self, other: &[T], keypath: K) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R> + Clone,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq;
+    
+    /// Elements whose keypath value occurs in exactly one of the collections, those of `self` first
+    fn symmetric_difference_by_keypath<R, K>(&self, other: &[T], keypath: K) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R> + Clone,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq;
+    
+    /// Elements of `self` whose value at `keypath` occurs at `other_keypath` in a collection of another type
+    fn intersect_by_keypaths<U, R1, R2, K1, K2>(
+        &self,
+        other: &[U],
+        keypath: K1,
+        other_keypath: K2,
+    ) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R1>,
+        T: Clone,
+        U: KeyPathRoot<R2>,
+        K1: KeyPathAccess<R1>,
+        K2: KeyPathAccess<R2> + for<'a> KeyPathAccessOutput<'a, R2, Output = AccessOutput<'a, K1, R1>>,
+        for<'a> AccessOutput<'a, K1, R1>: std::hash::Hash + Eq;
+    
+    /// Elements of `self` whose value at `keypath` does not occur at `other_keypath` in a
+    /// collection of another type
+    fn difference_by_keypaths<U, R1, R2, K1, K2>(
+        &self,
+        other: &[U],
+        keypath: K1,
+        other_keypath: K2,
+    ) -> KeyPathResult<Vec<T>>
     where
-        V: std::hash::Hash + Eq + Clone;
+        T: KeyPathRoot<R1>,
+        T: Clone,
+        U: KeyPathRoot<R2>,
+        K1: KeyPathAccess<R1>,
+        K2: KeyPathAccess<R2> + for<'a> KeyPathAccessOutput<'a, R2, Output = AccessOutput<'a, K1, R1>>,
+        for<'a> AccessOutput<'a, K1, R1>: std::hash::Hash + Eq;
     
     /// Zip with another collection using keypath values
-    fn zip_with_keypath<U, V1, V2, F, R>(
+    fn zip_with_keypath<U, R1, R2, K1, K2, F, R>(
         &self,
         other: &[U],
-        keypath1: KeyPaths<T, V1>,
-        keypath2: KeyPaths<U, V2>,
+        keypath1: K1,
+        keypath2: K2,
         f: F,
     ) -> KeyPathResult<Vec<R>>
     where
-        F: Fn(&V1, &V2) -> R;
+        T: KeyPathRoot<R1>,
+        U: KeyPathRoot<R2>,
+        K1: KeyPathAccess<R1>,
+        K2: KeyPathAccess<R2>,
+        F: for<'a> Fn(AccessOutput<'a, K1, R1>, AccessOutput<'a, K2, R2>) -> R;
     
     /// Window operations over keypath values
-    fn window_by_keypath<V, F, R>(
+    fn window_by_keypath<R, K, F, O>(
         &self,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         window_size: usize,
         f: F,
-    ) -> KeyPathResult<Vec<R>>
+    ) -> KeyPathResult<Vec<O>>
     where
-        V: Clone,
-        F: Fn(&[V]) -> R;
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        F: Fn(&[K::Value]) -> O;
     
     /// Rolling operations over keypath values
-    fn rolling_by_keypath<V, F, R>(
+    fn rolling_by_keypath<R, K, F, O>(
         &self,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         window_size: usize,
         f: F,
-    ) -> KeyPathResult<Vec<R>>
+    ) -> KeyPathResult<Vec<O>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        F: Fn(&[K::Value]) -> O;
+    
+    /// Mutate values in place through a writable keypath
+    fn for_each_keypath_mut<V, F>(&mut self, keypath: KeyPaths<T, V>, mut f: F) -> KeyPathResult<()>
+    where
+        Self: KeyPathsCollectionMutExt<T>,
+        F: FnMut(&mut V),
+    {
+        for item in self.keypath_elements_mut().iter_mut() {
+            let value = keypath.get_mut(item).ok_or_else(|| KeyPathError::InvalidAccess {
+                message: "KeyPath mutable access failed in for_each_keypath_mut".to_string(),
+            })?;
+            f(value);
+        }
+        Ok(())
+    }
+    
+    /// Mutate values at a writable keypath for elements matching a keypath condition
+    fn update_where<CK, V, P, U>(
+        &mut self,
+        condition_keypath: CK,
+        predicate: P,
+        target_keypath: KeyPaths<T, V>,
+        mut updater: U,
+    ) -> KeyPathResult<usize>
     where
-        V: Clone,
-        F: Fn(&[V]) -> R;
+        Self: KeyPathsCollectionMutExt<T>,
+        CK: KeyPathAccess<T>,
+        P: for<'a> Fn(AccessOutput<'a, CK, T>) -> bool,
+        U: FnMut(&mut V),
+    {
+        let mut updated = 0;
+        for item in self.keypath_elements_mut().iter_mut() {
+            let matches = {
+                let condition = condition_keypath.access(item).ok_or_else(|| KeyPathError::InvalidAccess {
+                    message: "KeyPath access failed in update_where".to_string(),
+                })?;
+                predicate(condition)
+            };
+            if matches {
+                let value = target_keypath.get_mut(item).ok_or_else(|| KeyPathError::InvalidAccess {
+                    message: "KeyPath mutable access failed in update_where".to_string(),
+                })?;
+                updater(value);
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
 }
 
-impl<T: KeyPathsOperable> KeyPathsCollectionExt<T> for Vec<T> {
-    fn collect_keypath<V>(&self, keypath: KeyPaths<T, V>) -> KeyPathResult<Vec<V>>
+impl<T, C> KeyPathsCollectionExt<T> for C
+where
+    C: ?Sized,
+    for<'a> &'a C: IntoIterator<Item = &'a T>,
+{
+    fn collect_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<K::Value>>
     where
-        V: Clone,
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
     {
-        let mut result = Vec::with_capacity(self.len());
+        let mut result = Vec::new();
             for item in self {
-                let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
                     panic!("KeyPath access failed in collect_keypath")
                 });
-                result.push(value.clone());
+                result.push(value);
             }
         Ok(result)
     }
     
-    fn partition_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<(Vec<T>, Vec<T>)>
+    fn partition_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<(Vec<T>, Vec<T>)>
     where
+        T: KeyPathRoot<R>,
         T: Clone,
-        F: Fn(&V) -> bool,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool,
     {
         let mut left = Vec::new();
         let mut right = Vec::new();
         
             for item in self {
-                let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
                     panic!("KeyPath access failed in partition_by_keypath")
                 });
                 if predicate(value) {
@@ -131,15 +812,17 @@ impl<T: KeyPathsOperable> KeyPathsCollectionExt<T> for Vec<T> {
         Ok((left, right))
     }
     
-    fn group_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, f: F) -> KeyPathResult<HashMap<V, Vec<T>>>
+    fn group_by_keypath<R, K, F>(&self, keypath: K, f: F) -> KeyPathResult<HashMap<K::Value, Vec<T>>>
     where
-        V: std::hash::Hash + Eq + Clone,
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq + Clone,
         T: Clone,
-        F: Fn(&V) -> V,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> K::Value,
     {
         let mut groups = HashMap::new();
         for item in self {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in group_by_keypath")
             });
             let key = f(value);
@@ -148,28 +831,14 @@ impl<T: KeyPathsOperable> KeyPathsCollectionExt<T> for Vec<T> {
         Ok(groups)
     }
     
-    fn sort_by_keypath<V, F>(&mut self, keypath: KeyPaths<T, V>, compare: F) -> KeyPathResult<()>
-    where
-        F: Fn(&V, &V) -> std::cmp::Ordering,
-    {
-        self.sort_by(|a, b| {
-            let a_val = a.get_at_keypath(&keypath).unwrap_or_else(|_| {
-                panic!("KeyPath access failed in sort_by_keypath")
-            });
-            let b_val = b.get_at_keypath(&keypath).unwrap_or_else(|_| {
-                panic!("KeyPath access failed in sort_by_keypath")
-            });
-            compare(a_val, b_val)
-        });
-        Ok(())
-    }
-    
-    fn find_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<Option<&T>>
+    fn find_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<Option<&T>>
     where
-        F: Fn(&V) -> bool,
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool,
     {
         for item in self {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in find_by_keypath")
             });
             if predicate(value) {
@@ -179,12 +848,14 @@ impl<T: KeyPathsOperable> KeyPathsCollectionExt<T> for Vec<T> {
         Ok(None)
     }
     
-    fn any_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<bool>
+    fn any_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<bool>
     where
-        F: Fn(&V) -> bool,
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool,
     {
         for item in self {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in any_by_keypath")
             });
             if predicate(value) {
@@ -194,12 +865,14 @@ impl<T: KeyPathsOperable> KeyPathsCollectionExt<T> for Vec<T> {
         Ok(false)
     }
     
-    fn all_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<bool>
+    fn all_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<bool>
     where
-        F: Fn(&V) -> bool,
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool,
     {
         for item in self {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in all_by_keypath")
             });
             if !predicate(value) {
@@ -209,13 +882,15 @@ impl<T: KeyPathsOperable> KeyPathsCollectionExt<T> for Vec<T> {
         Ok(true)
     }
     
-    fn count_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<usize>
+    fn count_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<usize>
     where
-        F: Fn(&V) -> bool,
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool,
     {
         let mut count = 0;
         for item in self {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in count_by_keypath")
             });
             if predicate(value) {
@@ -225,155 +900,973 @@ impl<T: KeyPathsOperable> KeyPathsCollectionExt<T> for Vec<T> {
         Ok(count)
     }
     
-    fn unique_by_keypath<V>(&self, keypath: KeyPaths<T, V>) -> KeyPathResult<HashSet<V>>
+    fn unique_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<HashSet<K::Value>>
     where
-        V: std::hash::Hash + Eq + Clone,
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq,
     {
         let mut unique = HashSet::new();
         for item in self {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in unique_by_keypath")
             });
-            unique.insert(value.clone());
+            unique.insert(value);
         }
         Ok(unique)
     }
     
-    fn distinct_by_keypath<V>(&self, keypath: KeyPaths<T, V>) -> KeyPathResult<HashMap<V, usize>>
+    fn distinct_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<HashMap<K::Value, usize>>
     where
-        V: std::hash::Hash + Eq + Clone,
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq,
     {
         let mut counts = HashMap::new();
         for item in self {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in distinct_by_keypath")
             });
-            *counts.entry(value.clone()).or_insert(0) += 1;
+            *counts.entry(value).or_insert(0) += 1;
         }
         Ok(counts)
     }
     
-    fn zip_with_keypath<U, V1, V2, F, R>(
-        &self,
-        other: &[U],
-        keypath1: KeyPaths<T, V1>,
-        keypath2: KeyPaths<U, V2>,
-        f: F,
-    ) -> KeyPathResult<Vec<R>>
+    fn dedup_by_keypath<R, K>(&self, keypath: K, keep: KeepOccurrence) -> KeyPathResult<Vec<T>>
     where
-        U: KeyPathsOperable,
-        F: Fn(&V1, &V2) -> R,
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R>,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq,
     {
-        let min_len = self.len().min(other.len());
-        let mut result = Vec::with_capacity(min_len);
+        let values: Vec<AccessOutput<'_, K, R>> = self
+            .into_iter()
+            .map(|item| keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in dedup_by_keypath")
+            }))
+            .collect();
         
-        for i in 0..min_len {
-            let value1 = self[i].get_at_keypath(&keypath1).unwrap_or_else(|_| {
-                panic!("KeyPath access failed in zip_with_keypath")
-            });
-            let value2 = other[i].get_at_keypath(&keypath2).unwrap_or_else(|_| {
-                panic!("KeyPath access failed in zip_with_keypath")
-            });
-            result.push(f(value1, value2));
+        // Index of the occurrence to keep for every distinct value
+        let mut kept: HashMap<&AccessOutput<'_, K, R>, usize> = HashMap::new();
+        for (index, value) in values.iter().enumerate() {
+            match keep {
+                KeepOccurrence::First => {
+                    kept.entry(value).or_insert(index);
+                }
+                KeepOccurrence::Last => {
+                    kept.insert(value, index);
+                }
+            }
         }
         
-        Ok(result)
+        Ok(self
+            .into_iter()
+            .zip(&values)
+            .enumerate()
+            .filter(|(index, (_, value))| kept[value] == *index)
+            .map(|(_, (item, _))| item.clone())
+            .collect())
     }
     
-    fn window_by_keypath<V, F, R>(
-        &self,
-        keypath: KeyPaths<T, V>,
-        window_size: usize,
-        f: F,
-    ) -> KeyPathResult<Vec<R>>
+    fn duplicates_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<(K::Value, Vec<T>)>>
     where
-        V: Clone,
-        F: Fn(&[V]) -> R,
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq + Clone,
     {
-        if window_size == 0 || window_size > self.len() {
-            return Err(KeyPathError::CollectionError {
-                message: format!("Invalid window size: {}", window_size),
+        let mut groups: Vec<(K::Value, Vec<T>)> = Vec::new();
+        let mut positions: HashMap<K::Value, usize> = HashMap::new();
+        for item in self {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in duplicates_by_keypath")
             });
+            match positions.get(&value) {
+                Some(&position) => groups[position].1.push(item.clone()),
+                None => {
+                    positions.insert(value.clone(), groups.len());
+                    groups.push((value, vec![item.clone()]));
+                }
+            }
         }
-        
-        let mut result = Vec::new();
-        for i in 0..=self.len() - window_size {
-            let window: Vec<V> = self[i..i + window_size]
-                .iter()
-                .map(|item| item.get_at_keypath(&keypath).unwrap_or_else(|_| {
-                    panic!("KeyPath access failed in window_by_keypath")
-                }).clone())
-                .collect();
-            result.push(f(&window));
-        }
-        
-        Ok(result)
+        groups.retain(|(_, items)| items.len() > 1);
+        Ok(groups)
     }
     
-    fn rolling_by_keypath<V, F, R>(
-        &self,
-        keypath: KeyPaths<T, V>,
-        window_size: usize,
-        f: F,
-    ) -> KeyPathResult<Vec<R>>
+    fn is_unique_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<bool>
     where
-        V: Clone,
-        F: Fn(&[V]) -> R,
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq,
     {
-        if window_size == 0 {
-            return Err(KeyPathError::CollectionError {
-                message: "Window size must be greater than 0".to_string(),
-            });
-        }
-        
-        let mut result = Vec::new();
-        let mut window = Vec::with_capacity(window_size);
-        
+        let mut seen = HashSet::new();
         for item in self {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
-                panic!("KeyPath access failed in rolling_by_keypath")
-            }).clone();
-            window.push(value);
-            
-            if window.len() == window_size {
-                result.push(f(&window));
-                window.remove(0);
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in is_unique_by_keypath")
+            });
+            if !seen.insert(value) {
+                return Ok(false);
             }
         }
-        
-        Ok(result)
+        Ok(true)
     }
-}
-
-/// Specialized collection operations for different data structures
-pub mod specialized {
-    use super::*;
     
-    /// Operations for HashMap collections
-    pub trait KeyPathsHashMapExt<K, V> {
+    fn top_k_by_keypath<R, K, F>(&self, keypath: K, k: usize, compare: F) -> KeyPathResult<Vec<&T>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering,
+    {
+        let entries = self.into_iter().enumerate().map(|(index, item)| {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in top_k_by_keypath")
+            });
+            (value, index, item)
+        });
+        let ranked = utils::select_ranked(entries, k, &|a: &K::Value, b: &K::Value| compare(b, a));
+        Ok(ranked.into_iter().map(|(_, _, item)| item).collect())
+    }
+    
+    fn bottom_k_by_keypath<R, K, F>(&self, keypath: K, k: usize, compare: F) -> KeyPathResult<Vec<&T>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering,
+    {
+        let entries = self.into_iter().enumerate().map(|(index, item)| {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in bottom_k_by_keypath")
+            });
+            (value, index, item)
+        });
+        let ranked = utils::select_ranked(entries, k, &compare);
+        Ok(ranked.into_iter().map(|(_, _, item)| item).collect())
+    }
+    
+    fn value_counts_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<(K::Value, usize)>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq + Clone,
+    {
+        let mut counts: Vec<(K::Value, usize)> = Vec::new();
+        let mut positions: HashMap<K::Value, usize> = HashMap::new();
+        for item in self {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in value_counts_by_keypath")
+            });
+            match positions.get(&value) {
+                Some(&position) => counts[position].1 += 1,
+                None => {
+                    positions.insert(value.clone(), counts.len());
+                    counts.push((value, 1));
+                }
+            }
+        }
+        // Stable sort keeps first-occurrence order among equal counts
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        Ok(counts)
+    }
+    
+    fn histogram_by_keypath<R, K>(&self, keypath: K, binning: Binning) -> KeyPathResult<Vec<HistogramBucket>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: HistogramValue,
+    {
+        Ok(self
+            .histogram_elements_by_keypath(keypath, binning)?
+            .into_iter()
+            .map(|(bucket, _)| bucket)
+            .collect())
+    }
+    
+    fn histogram_elements_by_keypath<R, K>(
+        &self,
+        keypath: K,
+        binning: Binning,
+    ) -> KeyPathResult<Vec<(HistogramBucket, Vec<&T>)>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: HistogramValue,
+    {
+        let values: Vec<(f64, &T)> = self
+            .into_iter()
+            .map(|item| {
+                let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in histogram_by_keypath")
+                });
+                (value.to_f64(), item)
+            })
+            .collect();
+        let numbers: Vec<f64> = values.iter().map(|(value, _)| *value).filter(|value| value.is_finite()).collect();
+        let edges = bin_edges(&binning, &numbers)?;
+        
+        let mut buckets: Vec<(HistogramBucket, Vec<&T>)> = edges
+            .windows(2)
+            .map(|pair| (HistogramBucket { lower: pair[0], upper: pair[1], count: 0 }, Vec::new()))
+            .collect();
+        for (value, item) in values {
+            if let Some(index) = bucket_index(&edges, value) {
+                buckets[index].0.count += 1;
+                buckets[index].1.push(item);
+            }
+        }
+        Ok(buckets)
+    }
+    
+    fn pivot_by_keypaths<R, RK, CK, VK, A, F>(
+        &self,
+        row_keypath: RK,
+        column_keypath: CK,
+        value_keypath: VK,
+        agg: F,
+        fill: A,
+    ) -> KeyPathResult<PivotTable<RK::Value, CK::Value, A>>
+    where
+        T: KeyPathRoot<R>,
+        RK: OwnedKeyPathAccess<R>,
+        RK::Value: std::hash::Hash + Eq + Clone,
+        CK: OwnedKeyPathAccess<R>,
+        CK::Value: std::hash::Hash + Eq + Clone,
+        VK: KeyPathAccess<R>,
+        for<'a> AccessOutput<'a, VK, R>: Clone,
+        A: Clone,
+        F: for<'a> Fn(&[AccessOutput<'a, VK, R>]) -> A,
+    {
+        let mut rows: Vec<RK::Value> = Vec::new();
+        let mut row_index: HashMap<RK::Value, usize> = HashMap::new();
+        let mut columns: Vec<CK::Value> = Vec::new();
+        let mut column_index: HashMap<CK::Value, usize> = HashMap::new();
+        let mut entries: Vec<(usize, usize, AccessOutput<'_, VK, R>)> = Vec::new();
+        
+        for item in self {
+            let root = item.keypath_root();
+            let (row, column, value) = match (
+                row_keypath.access_owned(root),
+                column_keypath.access_owned(root),
+                value_keypath.access(root),
+            ) {
+                (Some(row), Some(column), Some(value)) => (row, column, value),
+                _ => panic!("KeyPath access failed in pivot_by_keypaths"),
+            };
+            
+            let row = *row_index.entry(row.clone()).or_insert_with(|| {
+                rows.push(row);
+                rows.len() - 1
+            });
+            let column = *column_index.entry(column.clone()).or_insert_with(|| {
+                columns.push(column);
+                columns.len() - 1
+            });
+            entries.push((row, column, value));
+        }
+        
+        let mut cell_values: Vec<Vec<Vec<AccessOutput<'_, VK, R>>>> = vec![vec![Vec::new(); columns.len()]; rows.len()];
+        let mut row_values: Vec<Vec<AccessOutput<'_, VK, R>>> = vec![Vec::new(); rows.len()];
+        let mut column_values: Vec<Vec<AccessOutput<'_, VK, R>>> = vec![Vec::new(); columns.len()];
+        for (row, column, value) in &entries {
+            cell_values[*row][*column].push(value.clone());
+            row_values[*row].push(value.clone());
+            column_values[*column].push(value.clone());
+        }
+        let all_values: Vec<AccessOutput<'_, VK, R>> = entries.into_iter().map(|(_, _, value)| value).collect();
+        
+        let cells = cell_values
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|values| if values.is_empty() { fill.clone() } else { agg(values) })
+                    .collect()
+            })
+            .collect();
+        
+        Ok(PivotTable {
+            rows,
+            columns,
+            cells,
+            row_totals: row_values.iter().map(|values| agg(values)).collect(),
+            column_totals: column_values.iter().map(|values| agg(values)).collect(),
+            grand_total: if all_values.is_empty() { fill } else { agg(&all_values) },
+        })
+    }
+    
+    fn crosstab_by_keypaths<R, RK, CK>(
+        &self,
+        row_keypath: RK,
+        column_keypath: CK,
+    ) -> KeyPathResult<PivotTable<RK::Value, CK::Value, usize>>
+    where
+        T: KeyPathRoot<R>,
+        RK: OwnedKeyPathAccess<R> + Clone,
+        RK::Value: std::hash::Hash + Eq + Clone,
+        for<'a> AccessOutput<'a, RK, R>: Clone,
+        CK: OwnedKeyPathAccess<R>,
+        CK::Value: std::hash::Hash + Eq + Clone,
+    {
+        let rows = row_keypath.clone();
+        self.pivot_by_keypaths(row_keypath, column_keypath, rows, |values| values.len(), 0)
+    }
+    
+    fn scan_keypath<R, K, B, F>(&self, keypath: K, init: B, f: F) -> KeyPathResult<Vec<B>>
+    where
+        T: KeyPathRoot<R>,
+        B: Clone,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(B, AccessOutput<'a, K, R>) -> B,
+    {
+        let mut result = Vec::new();
+        let mut acc = init;
+        for item in self {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in scan_keypath")
+            });
+            acc = f(acc, value);
+            result.push(acc.clone());
+        }
+        Ok(result)
+    }
+    
+    fn cumulative_sum_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<K::Value>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: Clone + std::ops::Add<Output = K::Value>,
+    {
+        let mut result: Vec<K::Value> = Vec::new();
+        for item in self {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in cumulative_sum_by_keypath")
+            });
+            let total = match result.last() {
+                Some(previous) => previous.clone() + value,
+                None => value,
+            };
+            result.push(total);
+        }
+        Ok(result)
+    }
+    
+    fn cumulative_max_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<K::Value>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: Clone + PartialOrd,
+    {
+        let mut result: Vec<K::Value> = Vec::new();
+        for item in self {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in cumulative_max_by_keypath")
+            });
+            let max = match result.last() {
+                Some(previous) if *previous >= value => previous.clone(),
+                _ => value,
+            };
+            result.push(max);
+        }
+        Ok(result)
+    }
+    
+    fn running_count_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<Vec<usize>>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool,
+    {
+        let mut result = Vec::new();
+        let mut count = 0;
+        for item in self {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in running_count_by_keypath")
+            });
+            if predicate(value) {
+                count += 1;
+            }
+            result.push(count);
+        }
+        Ok(result)
+    }
+    
+    fn diff_by_keypath<R, K>(&self, keypath: K) -> KeyPathResult<Vec<K::Value>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: Clone + std::ops::Sub<Output = K::Value>,
+    {
+        self.diff_by_keypath_with(keypath, |current, previous| current - previous)
+    }
+    
+    fn diff_by_keypath_with<R, K, D, F>(&self, keypath: K, difference: F) -> KeyPathResult<Vec<D>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        K::Value: Clone,
+        F: Fn(K::Value, K::Value) -> D,
+    {
+        let mut result = Vec::new();
+        let mut previous: Option<K::Value> = None;
+        for item in self {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in diff_by_keypath")
+            });
+            if let Some(previous) = previous.take() {
+                result.push(difference(value.clone(), previous));
+            }
+            previous = Some(value);
+        }
+        Ok(result)
+    }
+    
+    fn union_by_keypath<R, K>(&self, other: &[T], keypath: K) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R>,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq,
+    {
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        for item in self.into_iter().chain(other) {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in union_by_keypath")
+            });
+            if seen.insert(value) {
+                result.push(item.clone());
+            }
+        }
+        Ok(result)
+    }
+    
+    fn intersect_by_keypath<R, K>(&self, other: &[T], keypath: K) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R> + Clone,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq,
+    {
+        self.intersect_by_keypaths(other, keypath.clone(), keypath)
+    }
+    
+    fn difference_by_keypath<R, K>(&self, other: &[T], keypath: K) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R> + Clone,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq,
+    {
+        self.difference_by_keypaths(other, keypath.clone(), keypath)
+    }
+    
+    fn symmetric_difference_by_keypath<R, K>(&self, other: &[T], keypath: K) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R>,
+        T: Clone,
+        K: KeyPathAccess<R> + Clone,
+        for<'a> AccessOutput<'a, K, R>: std::hash::Hash + Eq,
+    {
+        let mut result = self.difference_by_keypath(other, keypath.clone())?;
+        let these: HashSet<AccessOutput<'_, K, R>> = self
+            .into_iter()
+            .map(|item| keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in symmetric_difference_by_keypath")
+            }))
+            .collect();
+        let mut seen = HashSet::new();
+        for item in other {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in symmetric_difference_by_keypath")
+            });
+            if !these.contains(&value) && seen.insert(value) {
+                result.push(item.clone());
+            }
+        }
+        Ok(result)
+    }
+    
+    fn intersect_by_keypaths<U, R1, R2, K1, K2>(
+        &self,
+        other: &[U],
+        keypath: K1,
+        other_keypath: K2,
+    ) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R1>,
+        T: Clone,
+        U: KeyPathRoot<R2>,
+        K1: KeyPathAccess<R1>,
+        K2: KeyPathAccess<R2> + for<'a> KeyPathAccessOutput<'a, R2, Output = AccessOutput<'a, K1, R1>>,
+        for<'a> AccessOutput<'a, K1, R1>: std::hash::Hash + Eq,
+    {
+        let others: HashSet<AccessOutput<'_, K1, R1>> = other
+            .iter()
+            .map(|item| other_keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in intersect_by_keypaths")
+            }))
+            .collect();
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        for item in self {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in intersect_by_keypaths")
+            });
+            if others.contains(&value) && seen.insert(value) {
+                result.push(item.clone());
+            }
+        }
+        Ok(result)
+    }
+    
+    fn difference_by_keypaths<U, R1, R2, K1, K2>(
+        &self,
+        other: &[U],
+        keypath: K1,
+        other_keypath: K2,
+    ) -> KeyPathResult<Vec<T>>
+    where
+        T: KeyPathRoot<R1>,
+        T: Clone,
+        U: KeyPathRoot<R2>,
+        K1: KeyPathAccess<R1>,
+        K2: KeyPathAccess<R2> + for<'a> KeyPathAccessOutput<'a, R2, Output = AccessOutput<'a, K1, R1>>,
+        for<'a> AccessOutput<'a, K1, R1>: std::hash::Hash + Eq,
+    {
+        let others: HashSet<AccessOutput<'_, K1, R1>> = other
+            .iter()
+            .map(|item| other_keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in difference_by_keypaths")
+            }))
+            .collect();
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        for item in self {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in difference_by_keypaths")
+            });
+            if !others.contains(&value) && seen.insert(value) {
+                result.push(item.clone());
+            }
+        }
+        Ok(result)
+    }
+    
+    fn zip_with_keypath<U, R1, R2, K1, K2, F, R>(
+        &self,
+        other: &[U],
+        keypath1: K1,
+        keypath2: K2,
+        f: F,
+    ) -> KeyPathResult<Vec<R>>
+    where
+        T: KeyPathRoot<R1>,
+        U: KeyPathRoot<R2>,
+        K1: KeyPathAccess<R1>,
+        K2: KeyPathAccess<R2>,
+        F: for<'a> Fn(AccessOutput<'a, K1, R1>, AccessOutput<'a, K2, R2>) -> R,
+    {
+        let mut result = Vec::with_capacity(other.len());
+        
+        for (left, right) in self.into_iter().zip(other) {
+            let value1 = keypath1.access(left.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in zip_with_keypath")
+            });
+            let value2 = keypath2.access(right.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in zip_with_keypath")
+            });
+            result.push(f(value1, value2));
+        }
+        
+        Ok(result)
+    }
+    
+    fn window_by_keypath<R, K, F, O>(
+        &self,
+        keypath: K,
+        window_size: usize,
+        f: F,
+    ) -> KeyPathResult<Vec<O>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        F: Fn(&[K::Value]) -> O,
+    {
+        let len = self.into_iter().count();
+        if window_size == 0 || window_size > len {
+            return Err(KeyPathError::CollectionError {
+                message: format!("Invalid window size: {}", window_size),
+            });
+        }
+        
+        let values: Vec<K::Value> = self
+            .into_iter()
+            .map(|item| keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in window_by_keypath")
+            }))
+            .collect();
+        
+        Ok(values.windows(window_size).map(&f).collect())
+    }
+    
+
+    fn rolling_by_keypath<R, K, F, O>(
+        &self,
+        keypath: K,
+        window_size: usize,
+        f: F,
+    ) -> KeyPathResult<Vec<O>>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        F: Fn(&[K::Value]) -> O,
+    {
+        if window_size == 0 {
+            return Err(KeyPathError::CollectionError {
+                message: "Window size must be greater than 0".to_string(),
+            });
+        }
+        
+        let mut result = Vec::new();
+        let mut window = Vec::with_capacity(window_size);
+        
+        for item in self {
+            let value = keypath.access_owned(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in rolling_by_keypath")
+            });
+            window.push(value);
+            
+            if window.len() == window_size {
+                result.push(f(&window));
+                window.remove(0);
+            }
+        }
+        
+        Ok(result)
+    }
+}
+
+/// Collections whose elements can be sorted and mutated in place through keypaths
+///
+/// Implemented for `[T]`, `Vec<T>`, `[T; N]`, `Box<[T]>` and `VecDeque<T>`. It is what
+/// `KeyPathsCollectionExt::sort_by_keypath`, `for_each_keypath_mut` and `update_where` require.
+pub trait KeyPathsCollectionMutExt<T> {
+    /// The elements as one mutable slice, in order
+    fn keypath_elements_mut(&mut self) -> &mut [T];
+
+    /// Sort elements by keypath values, reading each value once
+    ///
+    /// Decorate-sort-undecorate, so expensive keypaths are read n times rather than on every
+    /// comparison. The comparison receives `&V`, as with `sort_by_keypath`.
+    fn sort_by_cached_keypath<R, K, F>(&mut self, keypath: K, compare: F) -> KeyPathResult<()>
+    where
+        T: KeyPathRoot<R>,
+        K: OwnedKeyPathAccess<R>,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering,
+    {
+        crate::higher_order::sort_by_cached_keypath(self.keypath_elements_mut(), keypath, compare)
+    }
+}
+
+impl<T> KeyPathsCollectionMutExt<T> for [T] {
+    fn keypath_elements_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> KeyPathsCollectionMutExt<T> for Vec<T> {
+    fn keypath_elements_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> KeyPathsCollectionMutExt<T> for [T; N] {
+    fn keypath_elements_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> KeyPathsCollectionMutExt<T> for Box<[T]> {
+    fn keypath_elements_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> KeyPathsCollectionMutExt<T> for VecDeque<T> {
+    fn keypath_elements_mut(&mut self) -> &mut [T] {
+        self.make_contiguous()
+    }
+}
+
+/// Extension trait for searching collections already sorted by a keypath
+///
+/// Implemented for `[T]` (and so for `Vec<T>`, `[T; N]` and `Box<[T]>`) and `VecDeque<T>`.
+/// The searches assume the collection is sorted by the same keypath and comparison, e.g. with
+/// `sort_by_keypath`, and run in O(log n).
+pub trait KeyPathsSortedExt<T> {
+    /// Binary search for an element whose keypath value equals `target`
+    ///
+    /// Returns `Ok(index)` of a matching element, or `Err(index)` where `target` could be inserted
+    /// while keeping the order, like `slice::binary_search_by`.
+    fn binary_search_by_keypath<R, K, F>(
+        &self,
+        keypath: K,
+        target: &K::Value,
+        compare: F,
+    ) -> KeyPathResult<Result<usize, usize>>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, &K::Value) -> std::cmp::Ordering;
+    
+    /// Index of the first element whose keypath value does not match `predicate`
+    ///
+    /// The collection must be partitioned: every matching element precedes every other element.
+    fn partition_point_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<usize>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool;
+    
+    /// Range of indices of the elements whose keypath value equals `target`
+    fn equal_range_by_keypath<R, K, F>(
+        &self,
+        keypath: K,
+        target: &K::Value,
+        compare: F,
+    ) -> KeyPathResult<std::ops::Range<usize>>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R> + Clone,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, &K::Value) -> std::cmp::Ordering;
+    
+    /// Check whether elements are in non-decreasing keypath order
+    fn is_sorted_by_keypath<R, K, F>(&self, keypath: K, compare: F) -> KeyPathResult<bool>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, AccessOutput<'a, K, R>) -> std::cmp::Ordering;
+}
+
+impl<T> KeyPathsSortedExt<T> for [T] {
+    fn binary_search_by_keypath<R, K, F>(
+        &self,
+        keypath: K,
+        target: &K::Value,
+        compare: F,
+    ) -> KeyPathResult<Result<usize, usize>>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, &K::Value) -> std::cmp::Ordering,
+    {
+        Ok(self.binary_search_by(|item| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in binary_search_by_keypath")
+            });
+            compare(value, target)
+        }))
+    }
+    
+    fn partition_point_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<usize>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool,
+    {
+        Ok(self.partition_point(|item| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in partition_point_by_keypath")
+            });
+            predicate(value)
+        }))
+    }
+    
+    fn equal_range_by_keypath<R, K, F>(
+        &self,
+        keypath: K,
+        target: &K::Value,
+        compare: F,
+    ) -> KeyPathResult<std::ops::Range<usize>>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R> + Clone,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, &K::Value) -> std::cmp::Ordering,
+    {
+        let start = self.partition_point_by_keypath(keypath.clone(), |value| compare(value, target).is_lt())?;
+        let end = self.partition_point_by_keypath(keypath, |value| compare(value, target).is_le())?;
+        Ok(start..end)
+    }
+    
+    fn is_sorted_by_keypath<R, K, F>(&self, keypath: K, compare: F) -> KeyPathResult<bool>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, AccessOutput<'a, K, R>) -> std::cmp::Ordering,
+    {
+        is_sorted_by_keypath_values(self.iter(), keypath, compare)
+    }
+}
+
+impl<T> KeyPathsSortedExt<T> for VecDeque<T> {
+    fn binary_search_by_keypath<R, K, F>(
+        &self,
+        keypath: K,
+        target: &K::Value,
+        compare: F,
+    ) -> KeyPathResult<Result<usize, usize>>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, &K::Value) -> std::cmp::Ordering,
+    {
+        Ok(self.binary_search_by(|item| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in binary_search_by_keypath")
+            });
+            compare(value, target)
+        }))
+    }
+    
+    fn partition_point_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<usize>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool,
+    {
+        Ok(self.partition_point(|item| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
+                panic!("KeyPath access failed in partition_point_by_keypath")
+            });
+            predicate(value)
+        }))
+    }
+    
+    fn equal_range_by_keypath<R, K, F>(
+        &self,
+        keypath: K,
+        target: &K::Value,
+        compare: F,
+    ) -> KeyPathResult<std::ops::Range<usize>>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R> + Clone,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, &K::Value) -> std::cmp::Ordering,
+    {
+        let start = self.partition_point_by_keypath(keypath.clone(), |value| compare(value, target).is_lt())?;
+        let end = self.partition_point_by_keypath(keypath, |value| compare(value, target).is_le())?;
+        Ok(start..end)
+    }
+    
+    fn is_sorted_by_keypath<R, K, F>(&self, keypath: K, compare: F) -> KeyPathResult<bool>
+    where
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, AccessOutput<'a, K, R>) -> std::cmp::Ordering,
+    {
+        is_sorted_by_keypath_values(self.iter(), keypath, compare)
+    }
+}
+
+fn is_sorted_by_keypath_values<'a, T, R, K, F>(
+    items: impl Iterator<Item = &'a T>,
+    keypath: K,
+    compare: F,
+) -> KeyPathResult<bool>
+where
+    T: KeyPathRoot<R> + 'a,
+    K: KeyPathAccess<R>,
+    F: for<'b> Fn(AccessOutput<'b, K, R>, AccessOutput<'b, K, R>) -> std::cmp::Ordering,
+{
+    let access = |item: &'a T| {
+        keypath.access(item.keypath_root()).unwrap_or_else(|| {
+            panic!("KeyPath access failed in is_sorted_by_keypath")
+        })
+    };
+    let mut previous: Option<&T> = None;
+    for item in items {
+        // Access both values again rather than keeping the previous one, so owned values
+        // need not be cloned
+        if previous.is_some_and(|previous| compare(access(previous), access(item)).is_gt()) {
+            return Ok(false);
+        }
+        previous = Some(item);
+    }
+    Ok(true)
+}
+
+/// Specialized collection operations for different data structures
+pub mod specialized {
+    use super::*;
+    use std::ops::RangeBounds;
+    
+    /// Operations for HashMap collections
+    pub trait KeyPathsHashMapExt<K, V> {
         /// Transform values using keypath
-        fn map_values_keypath<T, F, R>(&self, keypath: KeyPaths<V, T>, f: F) -> KeyPathResult<HashMap<K, R>>
+        fn map_values_keypath<P, F, R>(&self, keypath: P, f: F) -> KeyPathResult<HashMap<K, R>>
         where
+            P: KeyPathAccess<V>,
             K: Clone,
-            F: Fn(&T) -> R;
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> R;
         
         /// Filter by keypath predicate on values
-        fn filter_values_keypath<T, F>(&self, keypath: KeyPaths<V, T>, predicate: F) -> KeyPathResult<HashMap<K, V>>
+        fn filter_values_keypath<P, F>(&self, keypath: P, predicate: F) -> KeyPathResult<HashMap<K, V>>
         where
+            P: KeyPathAccess<V>,
             K: Clone,
             V: Clone,
-            F: Fn(&T) -> bool;
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool;
+        
+        /// Group entries by keypath values
+        fn group_values_by_keypath<P>(&self, keypath: P) -> KeyPathResult<HashMap<P::Value, HashMap<K, V>>>
+        where
+            P: OwnedKeyPathAccess<V>,
+            K: Clone,
+            V: Clone,
+            P::Value: std::hash::Hash + Eq;
+        
+        /// Fold keypath values of all entries
+        fn fold_values_keypath<P, B, F>(&self, keypath: P, init: B, f: F) -> KeyPathResult<B>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(B, AccessOutput<'a, P, V>) -> B;
+        
+        /// Find an entry whose keypath value matches a predicate
+        fn find_value_by_keypath<P, F>(&self, keypath: P, predicate: F) -> KeyPathResult<Option<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool;
+        
+        /// Entries sorted by keypath values
+        fn sort_entries_by_keypath<P, F>(&self, keypath: P, compare: F) -> KeyPathResult<Vec<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>, AccessOutput<'a, P, V>) -> std::cmp::Ordering;
+        
+        /// Re-key the values by keypath values
+        ///
+        /// Fails with a `CollectionError` if two values share a keypath value.
+        fn index_by_keypath<P>(&self, keypath: P) -> KeyPathResult<HashMap<P::Value, V>>
+        where
+            P: OwnedKeyPathAccess<V>,
+            V: Clone,
+            P::Value: std::hash::Hash + Eq;
+        
+        /// Keep only the entries whose keypath value matches a predicate
+        fn retain_by_keypath<P, F>(&mut self, keypath: P, predicate: F) -> KeyPathResult<()>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool;
     }
     
     impl<K: std::hash::Hash + std::cmp::Eq, V: KeyPathsOperable> KeyPathsHashMapExt<K, V> for HashMap<K, V> {
-        fn map_values_keypath<T, F, R>(&self, keypath: KeyPaths<V, T>, f: F) -> KeyPathResult<HashMap<K, R>>
+        fn map_values_keypath<P, F, R>(&self, keypath: P, f: F) -> KeyPathResult<HashMap<K, R>>
         where
+            P: KeyPathAccess<V>,
             K: Clone,
-            F: Fn(&T) -> R,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> R,
         {
             let mut result = HashMap::new();
             for (key, value) in self {
-                let keypath_value = value.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
                     panic!("KeyPath access failed in map_values_keypath")
                 });
                 result.insert(key.clone(), f(keypath_value));
@@ -381,15 +1874,16 @@ pub mod specialized {
             Ok(result)
         }
         
-        fn filter_values_keypath<T, F>(&self, keypath: KeyPaths<V, T>, predicate: F) -> KeyPathResult<HashMap<K, V>>
+        fn filter_values_keypath<P, F>(&self, keypath: P, predicate: F) -> KeyPathResult<HashMap<K, V>>
         where
+            P: KeyPathAccess<V>,
             K: Clone,
             V: Clone,
-            F: Fn(&T) -> bool,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool,
         {
             let mut result = HashMap::new();
             for (key, value) in self {
-                let keypath_value = keypath.get(value).unwrap_or_else(|| {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
                     panic!("KeyPath access failed in filter_values_keypath")
                 });
                 if predicate(keypath_value) {
@@ -398,33 +1892,196 @@ pub mod specialized {
             }
             Ok(result)
         }
+        
+        fn group_values_by_keypath<P>(&self, keypath: P) -> KeyPathResult<HashMap<P::Value, HashMap<K, V>>>
+        where
+            P: OwnedKeyPathAccess<V>,
+            K: Clone,
+            V: Clone,
+            P::Value: std::hash::Hash + Eq,
+        {
+            let mut groups: HashMap<P::Value, HashMap<K, V>> = HashMap::new();
+            for (key, value) in self {
+                let keypath_value = keypath.access_owned(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in group_values_by_keypath")
+                });
+                groups
+                    .entry(keypath_value)
+                    .or_default()
+                    .insert(key.clone(), value.clone());
+            }
+            Ok(groups)
+        }
+        
+        fn fold_values_keypath<P, B, F>(&self, keypath: P, init: B, f: F) -> KeyPathResult<B>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(B, AccessOutput<'a, P, V>) -> B,
+        {
+            let mut acc = init;
+            for value in self.values() {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in fold_values_keypath")
+                });
+                acc = f(acc, keypath_value);
+            }
+            Ok(acc)
+        }
+        
+        fn find_value_by_keypath<P, F>(&self, keypath: P, predicate: F) -> KeyPathResult<Option<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool,
+        {
+            for (key, value) in self {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in find_value_by_keypath")
+                });
+                if predicate(keypath_value) {
+                    return Ok(Some((key, value)));
+                }
+            }
+            Ok(None)
+        }
+        
+        fn sort_entries_by_keypath<P, F>(&self, keypath: P, compare: F) -> KeyPathResult<Vec<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>, AccessOutput<'a, P, V>) -> std::cmp::Ordering,
+        {
+            let mut entries: Vec<(&K, &V)> = self.iter().collect();
+            entries.sort_by(|(_, a), (_, b)| {
+                let a_val = keypath.access(*a).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in sort_entries_by_keypath")
+                });
+                let b_val = keypath.access(*b).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in sort_entries_by_keypath")
+                });
+                compare(a_val, b_val)
+            });
+            Ok(entries)
+        }
+        
+        fn index_by_keypath<P>(&self, keypath: P) -> KeyPathResult<HashMap<P::Value, V>>
+        where
+            P: OwnedKeyPathAccess<V>,
+            V: Clone,
+            P::Value: std::hash::Hash + Eq,
+        {
+            let mut index = HashMap::with_capacity(self.len());
+            for value in self.values() {
+                let keypath_value = keypath.access_owned(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in index_by_keypath")
+                });
+                if index.insert(keypath_value, value.clone()).is_some() {
+                    return Err(KeyPathError::CollectionError {
+                        message: "Duplicate keypath value in index_by_keypath".to_string(),
+                    });
+                }
+            }
+            Ok(index)
+        }
+        
+        fn retain_by_keypath<P, F>(&mut self, keypath: P, predicate: F) -> KeyPathResult<()>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool,
+        {
+            self.retain(|_, value| {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in retain_by_keypath")
+                });
+                predicate(keypath_value)
+            });
+            Ok(())
+        }
     }
     
     /// Operations for BTreeMap collections
     pub trait KeyPathsBTreeMapExt<K, V> {
         /// Transform values using keypath
-        fn map_values_keypath<T, F, R>(&self, keypath: KeyPaths<V, T>, f: F) -> KeyPathResult<BTreeMap<K, R>>
+        fn map_values_keypath<P, F, R>(&self, keypath: P, f: F) -> KeyPathResult<BTreeMap<K, R>>
         where
+            P: KeyPathAccess<V>,
             K: Clone + Ord,
-            F: Fn(&T) -> R;
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> R;
         
         /// Filter by keypath predicate on values
-        fn filter_values_keypath<T, F>(&self, keypath: KeyPaths<V, T>, predicate: F) -> KeyPathResult<BTreeMap<K, V>>
+        fn filter_values_keypath<P, F>(&self, keypath: P, predicate: F) -> KeyPathResult<BTreeMap<K, V>>
         where
+            P: KeyPathAccess<V>,
             K: Clone + Ord,
             V: Clone,
-            F: Fn(&T) -> bool;
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool;
+        
+        /// Group entries by keypath values, keeping each group ordered by key
+        fn group_values_by_keypath<P>(&self, keypath: P) -> KeyPathResult<HashMap<P::Value, BTreeMap<K, V>>>
+        where
+            P: OwnedKeyPathAccess<V>,
+            K: Clone + Ord,
+            V: Clone,
+            P::Value: std::hash::Hash + Eq;
+        
+        /// Fold keypath values of all entries, in key order
+        fn fold_values_keypath<P, B, F>(&self, keypath: P, init: B, f: F) -> KeyPathResult<B>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(B, AccessOutput<'a, P, V>) -> B;
+        
+        /// Find the first entry, in key order, whose keypath value matches a predicate
+        fn find_value_by_keypath<P, F>(&self, keypath: P, predicate: F) -> KeyPathResult<Option<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool;
+        
+        /// Entries sorted by keypath values, ties kept in key order
+        fn sort_entries_by_keypath<P, F>(&self, keypath: P, compare: F) -> KeyPathResult<Vec<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>, AccessOutput<'a, P, V>) -> std::cmp::Ordering;
+        
+        /// Re-key the values by keypath values
+        ///
+        /// Fails with a `CollectionError` if two values share a keypath value.
+        fn index_by_keypath<P>(&self, keypath: P) -> KeyPathResult<BTreeMap<P::Value, V>>
+        where
+            P: OwnedKeyPathAccess<V>,
+            V: Clone,
+            P::Value: Ord;
+        
+        /// Keep only the entries whose keypath value matches a predicate
+        fn retain_by_keypath<P, F>(&mut self, keypath: P, predicate: F) -> KeyPathResult<()>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool;
+        
+        /// Entries within a key range whose keypath value matches a predicate
+        fn range_by_keypath<R, P, F>(&self, range: R, keypath: P, predicate: F) -> KeyPathResult<Vec<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            R: RangeBounds<K>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool;
+        
+        /// Filter the entries within a key range by keypath predicate on values
+        fn filter_range_keypath<R, P, F>(&self, range: R, keypath: P, predicate: F) -> KeyPathResult<BTreeMap<K, V>>
+        where
+            P: KeyPathAccess<V>,
+            R: RangeBounds<K>,
+            K: Clone,
+            V: Clone,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool;
     }
     
     impl<K: std::cmp::Ord, V: KeyPathsOperable> KeyPathsBTreeMapExt<K, V> for BTreeMap<K, V> {
-        fn map_values_keypath<T, F, R>(&self, keypath: KeyPaths<V, T>, f: F) -> KeyPathResult<BTreeMap<K, R>>
+        fn map_values_keypath<P, F, R>(&self, keypath: P, f: F) -> KeyPathResult<BTreeMap<K, R>>
         where
+            P: KeyPathAccess<V>,
             K: Clone + Ord,
-            F: Fn(&T) -> R,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> R,
         {
             let mut result = BTreeMap::new();
             for (key, value) in self {
-                let keypath_value = value.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
                     panic!("KeyPath access failed in map_values_keypath")
                 });
                 result.insert(key.clone(), f(keypath_value));
@@ -432,15 +2089,16 @@ pub mod specialized {
             Ok(result)
         }
         
-        fn filter_values_keypath<T, F>(&self, keypath: KeyPaths<V, T>, predicate: F) -> KeyPathResult<BTreeMap<K, V>>
+        fn filter_values_keypath<P, F>(&self, keypath: P, predicate: F) -> KeyPathResult<BTreeMap<K, V>>
         where
+            P: KeyPathAccess<V>,
             K: Clone + Ord,
             V: Clone,
-            F: Fn(&T) -> bool,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool,
         {
             let mut result = BTreeMap::new();
             for (key, value) in self {
-                let keypath_value = keypath.get(value).unwrap_or_else(|| {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
                     panic!("KeyPath access failed in filter_values_keypath")
                 });
                 if predicate(keypath_value) {
@@ -449,12 +2107,243 @@ pub mod specialized {
             }
             Ok(result)
         }
+        
+        fn group_values_by_keypath<P>(&self, keypath: P) -> KeyPathResult<HashMap<P::Value, BTreeMap<K, V>>>
+        where
+            P: OwnedKeyPathAccess<V>,
+            K: Clone + Ord,
+            V: Clone,
+            P::Value: std::hash::Hash + Eq,
+        {
+            let mut groups: HashMap<P::Value, BTreeMap<K, V>> = HashMap::new();
+            for (key, value) in self {
+                let keypath_value = keypath.access_owned(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in group_values_by_keypath")
+                });
+                groups
+                    .entry(keypath_value)
+                    .or_default()
+                    .insert(key.clone(), value.clone());
+            }
+            Ok(groups)
+        }
+        
+        fn fold_values_keypath<P, B, F>(&self, keypath: P, init: B, f: F) -> KeyPathResult<B>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(B, AccessOutput<'a, P, V>) -> B,
+        {
+            let mut acc = init;
+            for value in self.values() {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in fold_values_keypath")
+                });
+                acc = f(acc, keypath_value);
+            }
+            Ok(acc)
+        }
+        
+        fn find_value_by_keypath<P, F>(&self, keypath: P, predicate: F) -> KeyPathResult<Option<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool,
+        {
+            for (key, value) in self {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in find_value_by_keypath")
+                });
+                if predicate(keypath_value) {
+                    return Ok(Some((key, value)));
+                }
+            }
+            Ok(None)
+        }
+        
+        fn sort_entries_by_keypath<P, F>(&self, keypath: P, compare: F) -> KeyPathResult<Vec<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>, AccessOutput<'a, P, V>) -> std::cmp::Ordering,
+        {
+            let mut entries: Vec<(&K, &V)> = self.iter().collect();
+            entries.sort_by(|(_, a), (_, b)| {
+                let a_val = keypath.access(*a).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in sort_entries_by_keypath")
+                });
+                let b_val = keypath.access(*b).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in sort_entries_by_keypath")
+                });
+                compare(a_val, b_val)
+            });
+            Ok(entries)
+        }
+        
+        fn index_by_keypath<P>(&self, keypath: P) -> KeyPathResult<BTreeMap<P::Value, V>>
+        where
+            P: OwnedKeyPathAccess<V>,
+            V: Clone,
+            P::Value: Ord,
+        {
+            let mut index = BTreeMap::new();
+            for value in self.values() {
+                let keypath_value = keypath.access_owned(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in index_by_keypath")
+                });
+                if index.insert(keypath_value, value.clone()).is_some() {
+                    return Err(KeyPathError::CollectionError {
+                        message: "Duplicate keypath value in index_by_keypath".to_string(),
+                    });
+                }
+            }
+            Ok(index)
+        }
+        
+        fn retain_by_keypath<P, F>(&mut self, keypath: P, predicate: F) -> KeyPathResult<()>
+        where
+            P: KeyPathAccess<V>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool,
+        {
+            self.retain(|_, value| {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in retain_by_keypath")
+                });
+                predicate(keypath_value)
+            });
+            Ok(())
+        }
+        
+        fn range_by_keypath<R, P, F>(&self, range: R, keypath: P, predicate: F) -> KeyPathResult<Vec<(&K, &V)>>
+        where
+            P: KeyPathAccess<V>,
+            R: RangeBounds<K>,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool,
+        {
+            let mut result = Vec::new();
+            for (key, value) in self.range(range) {
+                let keypath_value = keypath.access(value).unwrap_or_else(|| {
+                    panic!("KeyPath access failed in range_by_keypath")
+                });
+                if predicate(keypath_value) {
+                    result.push((key, value));
+                }
+            }
+            Ok(result)
+        }
+        
+        fn filter_range_keypath<R, P, F>(&self, range: R, keypath: P, predicate: F) -> KeyPathResult<BTreeMap<K, V>>
+        where
+            P: KeyPathAccess<V>,
+            R: RangeBounds<K>,
+            K: Clone,
+            V: Clone,
+            F: for<'a> Fn(AccessOutput<'a, P, V>) -> bool,
+        {
+            Ok(self
+                .range_by_keypath(range, keypath, predicate)?
+                .into_iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect())
+        }
     }
 }
 
 /// Utility functions for collection operations
 pub mod utils {
     use super::*;
+    use std::cmp::Ordering;
+    use std::collections::BinaryHeap;
+    
+    /// Heap entry ordered by rank, so the root of a max-heap is the worst entry kept so far
+    struct Ranked<'r, K, E, F> {
+        key: K,
+        index: usize,
+        element: E,
+        rank: &'r F,
+    }
+    
+    impl<K, E, F: Fn(&K, &K) -> Ordering> Ord for Ranked<'_, K, E, F> {
+        fn cmp(&self, other: &Self) -> Ordering {
+            (self.rank)(&self.key, &other.key).then(self.index.cmp(&other.index))
+        }
+    }
+    
+    impl<K, E, F: Fn(&K, &K) -> Ordering> PartialOrd for Ranked<'_, K, E, F> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    
+    impl<K, E, F: Fn(&K, &K) -> Ordering> PartialEq for Ranked<'_, K, E, F> {
+        fn eq(&self, other: &Self) -> bool {
+            self.cmp(other) == Ordering::Equal
+        }
+    }
+    
+    impl<K, E, F: Fn(&K, &K) -> Ordering> Eq for Ranked<'_, K, E, F> {}
+    
+    /// A heap keeping at most `k` `(key, index, element)` entries, discarding the worst-ranked
+    ///
+    /// `rank` orders keys best first; entries with equally ranked keys keep the lower index.
+    /// The heap grows as entries arrive rather than reserving `k` slots, so a `k` larger than the
+    /// input, up to `usize::MAX` for "keep everything", costs nothing extra.
+    pub(crate) struct BoundedHeap<'r, K, E, F> {
+        heap: BinaryHeap<Ranked<'r, K, E, F>>,
+        k: usize,
+        rank: &'r F,
+    }
+    
+    impl<'r, K, E, F: Fn(&K, &K) -> Ordering> BoundedHeap<'r, K, E, F> {
+        pub(crate) fn new(k: usize, rank: &'r F) -> Self {
+            Self {
+                heap: BinaryHeap::new(),
+                k,
+                rank,
+            }
+        }
+        
+        pub(crate) fn push(&mut self, key: K, index: usize, element: E) {
+            let entry = Ranked { key, index, element, rank: self.rank };
+            if self.heap.len() < self.k {
+                self.heap.push(entry);
+            } else if let Some(mut worst) = self.heap.peek_mut() {
+                if entry < *worst {
+                    *worst = entry;
+                }
+            }
+        }
+        
+        /// Add every entry kept by another heap
+        #[cfg(feature = "parallel")]
+        pub(crate) fn merge(&mut self, other: Self) {
+            for entry in other.heap {
+                self.push(entry.key, entry.index, entry.element);
+            }
+        }
+        
+        /// The kept entries, best first
+        pub(crate) fn into_sorted_vec(self) -> Vec<(K, usize, E)> {
+            self.heap
+                .into_sorted_vec()
+                .into_iter()
+                .map(|entry| (entry.key, entry.index, entry.element))
+                .collect()
+        }
+    }
+    
+    /// Keep the `k` best `(key, index, element)` entries, best first
+    pub(crate) fn select_ranked<K, E, F>(
+        entries: impl IntoIterator<Item = (K, usize, E)>,
+        k: usize,
+        rank: &F,
+    ) -> Vec<(K, usize, E)>
+    where
+        F: Fn(&K, &K) -> Ordering,
+    {
+        let mut heap = BoundedHeap::new(k, rank);
+        for (key, index, element) in entries {
+            heap.push(key, index, element);
+        }
+        heap.into_sorted_vec()
+    }
     
     /// Create a keypath-based comparator for sorting
     pub fn create_keypath_comparator<T: KeyPathsOperable, V, F>(
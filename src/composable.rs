@@ -28,9 +28,10 @@
 //! let result = pipe(people, |people| {
 //!     people.iter()
 //!         .filter_by_keypath(Person::age(), |&age| age < 30)
+//!         .into_iter()
 //!         .map_keypath(Person::name(), |name| name.clone())
-//!         .collect::<Vec<_>>()
 //! });
+//! assert_eq!(result, vec!["Bob"]);
 //! ```
 //!
 //! ### Conditional Operations
@@ -53,16 +54,17 @@
 //! ];
 //!
 //! // Apply discount only to electronics
-//! let discounted_products = products
+//! let discounted_prices = products
 //!     .iter()
 //!     .when_keypath(Product::category(), |cat| cat == "Electronics", |iter| {
-//!         iter.map_keypath(Product::price(), |&price| price * 0.9)
+//!         iter.map_keypath(Product::price(), |&price| price * 0.9).into_iter()
 //!     })
-//!     .collect::<Vec<_>>();
+//!     .unwrap();
 //! ```
 
 use key_paths_core::KeyPaths;
-use crate::error::KeyPathResult;
+use crate::error::{ErrorMode, KeyPathResult};
+use crate::traits::KeyPathRoot;
 
 /// Function composition for keypath operations
 /// 
@@ -88,8 +90,8 @@ use crate::error::KeyPathResult;
 /// let result: Vec<String> = pipe(people, |people| {
 ///     people.iter()
 ///         .filter_by_keypath(Person::age(), |&age| age < 30)
+///         .into_iter()
 ///         .map_keypath(Person::name(), |name| name.clone())
-///         .collect()
 /// });
 /// 
 /// assert_eq!(result, vec!["Bob"]);
@@ -132,8 +134,7 @@ where
 /// ];
 /// 
 /// // Chain multiple transformations
-/// let cities: Vec<String> = people
-///     .iter()
+/// let cities: Vec<String> = chain_keypath_ops(people)
 ///     .filter_by_keypath(Person::age(), |&age| age >= 30)
 ///     .map_keypath(Person::address().then(Address::city()), |city| city.clone())
 ///     .collect();
@@ -168,26 +169,27 @@ pub fn chain_keypath_ops<T>(collection: Vec<T>) -> KeyPathsChain<T> {
 /// let result: Vec<String> = people
 ///     .iter()
 ///     .filter_by_keypath(Person::age(), |&age| age >= 30)
-///     .map_keypath(Person::name(), |name| name.to_uppercase())
-///     .collect();
+///     .into_iter()
+///     .map_keypath(Person::name(), |name| name.to_uppercase());
 /// 
 /// assert_eq!(result, vec!["ALICE"]);
 /// ```
-pub fn when_keypath<T, V, F, G, R>(
-    collection: Vec<T>,
+pub fn when_keypath<I, T, V, F, G, R>(
+    collection: Vec<I>,
     keypath: KeyPaths<T, V>,
     condition: F,
     operation: G,
 ) -> KeyPathResult<Vec<R>>
 where
+    I: KeyPathRoot<T>,
     F: Fn(&V) -> bool,
-    G: FnOnce(std::vec::IntoIter<T>) -> std::vec::IntoIter<R>,
+    G: FnOnce(std::vec::IntoIter<I>) -> std::vec::IntoIter<R>,
 {
     let mut result = Vec::new();
     let mut iter = collection.into_iter();
     
     while let Some(item) = iter.next() {
-        let value = keypath.get(&item).unwrap_or_else(|| {
+        let value = keypath.get(item.keypath_root()).unwrap_or_else(|| {
             panic!("KeyPath access failed in when_keypath")
         });
         if condition(value) {
@@ -230,20 +232,21 @@ where
 /// let result: Vec<String> = people
 ///     .iter()
 ///     .filter_by_keypath(Person::age(), |&age| age < 30)
-///     .map_keypath(Person::name(), |name| name.to_uppercase())
-///     .collect();
+///     .into_iter()
+///     .map_keypath(Person::name(), |name| name.to_uppercase());
 /// 
 /// assert_eq!(result, vec!["BOB"]);
 /// ```
-pub fn unless_keypath<T, V, F, G, R>(
-    collection: Vec<T>,
+pub fn unless_keypath<I, T, V, F, G, R>(
+    collection: Vec<I>,
     keypath: KeyPaths<T, V>,
     condition: F,
     operation: G,
 ) -> KeyPathResult<Vec<R>>
 where
+    I: KeyPathRoot<T>,
     F: Fn(&V) -> bool,
-    G: FnOnce(std::vec::IntoIter<T>) -> std::vec::IntoIter<R>,
+    G: FnOnce(std::vec::IntoIter<I>) -> std::vec::IntoIter<R>,
 {
     when_keypath(collection, keypath, |v| !condition(v), operation)
 }
@@ -259,14 +262,15 @@ impl<T> KeyPathsChain<T> {
     }
     
     /// Filter by keypath predicate
-    pub fn filter_by_keypath<V, F>(self, keypath: KeyPaths<T, V>, predicate: F) -> Self
+    pub fn filter_by_keypath<K, V, F>(self, keypath: KeyPaths<K, V>, predicate: F) -> Self
     where
+        T: KeyPathRoot<K>,
         F: Fn(&V) -> bool,
     {
         let filtered: Vec<T> = self.collection
             .into_iter()
             .filter(|item| {
-                let value = keypath.get(item).unwrap_or_else(|| {
+                let value = keypath.get(item.keypath_root()).unwrap_or_else(|| {
                     panic!("KeyPath access failed in filter")
                 });
                 predicate(value)
@@ -276,14 +280,15 @@ impl<T> KeyPathsChain<T> {
     }
     
     /// Map over keypath values
-    pub fn map_keypath<V, F, R>(self, keypath: KeyPaths<T, V>, f: F) -> KeyPathsChain<R>
+    pub fn map_keypath<K, V, F, R>(self, keypath: KeyPaths<K, V>, f: F) -> KeyPathsChain<R>
     where
+        T: KeyPathRoot<K>,
         F: Fn(&V) -> R,
     {
         let mapped: Vec<R> = self.collection
             .into_iter()
             .map(|item| {
-                let value = keypath.get(&item).unwrap_or_else(|| {
+                let value = keypath.get(item.keypath_root()).unwrap_or_else(|| {
                     panic!("KeyPath access failed in map")
                 });
                 f(value)
@@ -293,13 +298,14 @@ impl<T> KeyPathsChain<T> {
     }
     
     /// Fold over keypath values
-    pub fn fold_keypath<V, F, B>(self, keypath: KeyPaths<T, V>, init: B, f: F) -> KeyPathResult<B>
+    pub fn fold_keypath<K, V, F, B>(self, keypath: KeyPaths<K, V>, init: B, f: F) -> KeyPathResult<B>
     where
+        T: KeyPathRoot<K>,
         F: Fn(B, &V) -> B,
     {
         let mut acc = init;
         for item in self.collection {
-            let value = keypath.get(&item).unwrap_or_else(|| {
+            let value = keypath.get(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in fold")
             });
             acc = f(acc, value);
@@ -307,6 +313,36 @@ impl<T> KeyPathsChain<T> {
         Ok(acc)
     }
     
+    /// Filter by a fallible keypath predicate
+    pub fn try_filter_by_keypath<K, V, F, E>(self, keypath: KeyPaths<K, V>, predicate: F, mode: ErrorMode) -> KeyPathResult<Self>
+    where
+        T: KeyPathRoot<K>,
+        F: Fn(&V) -> Result<bool, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        crate::higher_order::try_filter_by_keypath(self.collection, keypath, predicate, mode).map(Self::new)
+    }
+    
+    /// Map over keypath values with a fallible closure
+    pub fn try_map_keypath<K, V, F, R, E>(self, keypath: KeyPaths<K, V>, f: F, mode: ErrorMode) -> KeyPathResult<KeyPathsChain<R>>
+    where
+        T: KeyPathRoot<K>,
+        F: Fn(&V) -> Result<R, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        crate::higher_order::try_map_keypath_collection(&self.collection, keypath, f, mode).map(KeyPathsChain::new)
+    }
+    
+    /// Fold over keypath values with a fallible closure, stopping at the first error
+    pub fn try_fold_keypath<K, V, F, B, E>(self, keypath: KeyPaths<K, V>, init: B, f: F) -> KeyPathResult<B>
+    where
+        T: KeyPathRoot<K>,
+        F: Fn(B, &V) -> Result<B, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        crate::higher_order::try_fold_keypath(self.collection, keypath, init, f)
+    }
+    
     /// Collect into a vector
     pub fn collect<B: FromIterator<T>>(self) -> B {
         self.collection.into_iter().collect()
@@ -330,6 +366,51 @@ impl<T> KeyPathsChain<T> {
         reversed.reverse();
         Self::new(reversed)
     }
+    
+    /// Split into batches of `n` elements; the last batch may be shorter
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn batched(self, n: usize) -> KeyPathsChain<Vec<T>> {
+        assert!(n > 0, "Batch size must be greater than 0");
+        let mut batches = Vec::with_capacity(self.collection.len().div_ceil(n));
+        let mut items = self.collection.into_iter().peekable();
+        while items.peek().is_some() {
+            batches.push(items.by_ref().take(n).collect());
+        }
+        KeyPathsChain::new(batches)
+    }
+    
+    /// Split into runs of consecutive elements with equal keypath values
+    pub fn chunk_by_keypath<K, V>(self, keypath: KeyPaths<K, V>) -> KeyPathsChain<Vec<T>>
+    where
+        T: KeyPathRoot<K>,
+        V: PartialEq,
+    {
+        let chunks = crate::higher_order::chunk_by_keypath(self.collection, keypath).unwrap_or_else(|_| {
+            panic!("KeyPath access failed in chunk_by_keypath")
+        });
+        KeyPathsChain::new(chunks)
+    }
+    
+    /// Split into batches of at most `max_size` elements, never splitting elements that share
+    /// a keypath value across batches
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_size` is 0.
+    pub fn batched_by_keypath<K, V>(self, keypath: KeyPaths<K, V>, max_size: usize) -> KeyPathsChain<Vec<T>>
+    where
+        T: KeyPathRoot<K>,
+        V: std::hash::Hash + Eq + Clone,
+    {
+        assert!(max_size > 0, "Batch size must be greater than 0");
+        let batches = crate::higher_order::batched_by_keypath(self.collection, keypath, max_size).unwrap_or_else(|_| {
+            panic!("KeyPath access failed in batched_by_keypath")
+        });
+        KeyPathsChain::new(batches)
+    }
 }
 
 /// Extension trait for adding composable operations to iterators
@@ -352,14 +433,15 @@ pub trait ComposableIterator<T>: Iterator<Item = T> {
     }
     
     /// Apply operation when condition is met
-    fn when_keypath<V, F, G, R>(
+    fn when_keypath<K, V, F, G, R>(
         self,
-        keypath: KeyPaths<T, V>,
+        keypath: KeyPaths<K, V>,
         condition: F,
         operation: G,
     ) -> KeyPathResult<Vec<R>>
     where
         Self: Sized,
+        T: KeyPathRoot<K>,
         F: Fn(&V) -> bool,
         G: FnOnce(std::vec::IntoIter<T>) -> std::vec::IntoIter<R>,
     {
@@ -367,14 +449,15 @@ pub trait ComposableIterator<T>: Iterator<Item = T> {
     }
     
     /// Apply operation unless condition is met
-    fn unless_keypath<V, F, G, R>(
+    fn unless_keypath<K, V, F, G, R>(
         self,
-        keypath: KeyPaths<T, V>,
+        keypath: KeyPaths<K, V>,
         condition: F,
         operation: G,
     ) -> KeyPathResult<Vec<R>>
     where
         Self: Sized,
+        T: KeyPathRoot<K>,
         F: Fn(&V) -> bool,
         G: FnOnce(std::vec::IntoIter<T>) -> std::vec::IntoIter<R>,
     {
@@ -385,34 +468,276 @@ pub trait ComposableIterator<T>: Iterator<Item = T> {
 // Implement ComposableIterator for all iterators
 impl<I: Iterator> ComposableIterator<I::Item> for I {}
 
-/// Macro for creating keypath operation pipelines
+/// Build a keypath pipeline that expands into a single iterator chain
+///
+/// Operations are separated by commas and applied in order:
+///
+/// - `filter(keypath, predicate)` / `filter(predicate)`: Keep elements matching a predicate
+/// - `map(keypath, f)` / `map(f)`: Transform elements; later operations see the new values
+/// - `sort(keypath)` / `sort(keypath, compare)`: Stable sort by a keypath value
+/// - `take(n)` / `skip(n)`: Limit the elements
+/// - `join(other, keypath, other_keypath)`: Inner join with a slice on equal keypath values,
+///   yielding `(element, &other_element)` pairs
+/// - `group(keypath)`: Group into a `HashMap<V, Vec<_>>` (must come last)
+/// - `fold(keypath, init, f)`: Accumulate keypath values (must come last)
+///
+/// Everything except `sort` and `join`'s index is evaluated lazily, and `join` does not allocate
+/// per element. Pipelines not ending in `group` or `fold` collect into a `Vec`.
+///
+/// # Panics
+///
+/// Panics if a keypath cannot be read from an element, naming the failing operation.
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use rust_prelude_plus::keypath_pipeline;
+/// use key_paths_derive::Keypath;
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Person {
+///     id: u32,
+///     name: String,
+///     age: u32,
+/// }
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Order {
+///     customer_id: u32,
+///     total: f64,
+/// }
+///
+/// let people = vec![
+///     Person { id: 1, name: "Alice".to_string(), age: 30 },
+///     Person { id: 2, name: "Bob".to_string(), age: 17 },
+///     Person { id: 3, name: "Carol".to_string(), age: 45 },
+/// ];
+/// let orders = vec![
+///     Order { customer_id: 3, total: 20.0 },
+///     Order { customer_id: 1, total: 35.0 },
+///     Order { customer_id: 3, total: 5.0 },
+/// ];
+///
+/// let adults = keypath_pipeline!(people.clone() =>
+///     filter(Person::age(), |&age| age >= 18),
+///     sort(Person::name(), |a, b| b.cmp(a)),
+///     map(Person::name(), |name| name.clone()),
+/// );
+/// assert_eq!(adults, vec!["Carol", "Alice"]);
+///
+/// let spent = keypath_pipeline!(people =>
+///     join(&orders, Person::id(), Order::customer_id()),
+///     map(|(person, order)| (person.name, order.total)),
+/// );
+/// assert_eq!(spent, vec![("Alice".to_string(), 35.0), ("Carol".to_string(), 20.0), ("Carol".to_string(), 5.0)]);
+///
+/// let total = keypath_pipeline!(orders => skip(1), fold(Order::total(), 0.0, |acc, &total| acc + total));
+/// assert_eq!(total, 40.0);
+/// ```
+///
+/// Unknown operations are rejected at compile time:
+///
+/// ```compile_fail
+/// use rust_prelude_plus::keypath_pipeline;
+///
+/// let doubled = keypath_pipeline!(vec![1, 2, 3] => reverse());
+/// ```
 #[macro_export]
 macro_rules! keypath_pipeline {
-    ($collection:expr => $($op:tt)*) => {
-        {
-            let mut result = $collection;
-            $(
-                result = keypath_pipeline_op!(result, $op);
-            )*
-            result
-        }
+    (@chain $iter:expr ; $(,)?) => {
+        ::core::iter::Iterator::collect::<::std::vec::Vec<_>>($iter)
+    };
+    (@chain $iter:expr ; group($($args:tt)*) $(,)?) => {
+        $crate::keypath_pipeline_op!($iter, group($($args)*))
+    };
+    (@chain $iter:expr ; fold($($args:tt)*) $(,)?) => {
+        $crate::keypath_pipeline_op!($iter, fold($($args)*))
+    };
+    (@chain $iter:expr ; group($($args:tt)*), $($rest:tt)+) => {
+        ::core::compile_error!("keypath_pipeline!: `group` must be the last operation")
+    };
+    (@chain $iter:expr ; fold($($args:tt)*), $($rest:tt)+) => {
+        ::core::compile_error!("keypath_pipeline!: `fold` must be the last operation")
+    };
+    (@chain $iter:expr ; $op:ident($($args:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::keypath_pipeline!(@chain $crate::keypath_pipeline_op!($iter, $op($($args)*)) ; $($($rest)*)?)
+    };
+    (@chain $iter:expr ; $($rest:tt)+) => {
+        ::core::compile_error!(::core::concat!(
+            "keypath_pipeline!: expected an operation such as `filter(...)`, found `",
+            ::core::stringify!($($rest)+),
+            "`"
+        ))
+    };
+    ($collection:expr => $($ops:tt)*) => {
+        $crate::keypath_pipeline!(@chain ::core::iter::IntoIterator::into_iter($collection) ; $($ops)*)
     };
 }
 
+/// Apply a single `keypath_pipeline!` operation to an iterator
+///
+/// Stage operations return an iterator; `group` and `fold` return their result.
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use rust_prelude_plus::keypath_pipeline_op;
+/// use key_paths_derive::Keypath;
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Item {
+///     price: f64,
+/// }
+///
+/// let items = vec![Item { price: 3.0 }, Item { price: 1.0 }];
+/// let cheap: Vec<Item> = keypath_pipeline_op!(items.into_iter(), filter(Item::price(), |&price| price < 2.0)).collect();
+/// assert_eq!(cheap.len(), 1);
+/// ```
 #[macro_export]
 macro_rules! keypath_pipeline_op {
-    ($collection:expr, filter_by_keypath($keypath:expr, $predicate:expr)) => {
-        $collection.into_iter().filter_by_keypath($keypath, $predicate).collect()
+    ($iter:expr, filter($keypath:expr, $predicate:expr $(,)?)) => {
+        $crate::composable::pipeline::filter($iter, $keypath, $predicate)
+    };
+    ($iter:expr, filter($predicate:expr $(,)?)) => {
+        ::core::iter::Iterator::filter($iter, $predicate)
+    };
+    ($iter:expr, map($keypath:expr, $f:expr $(,)?)) => {
+        $crate::composable::pipeline::map($iter, $keypath, $f)
+    };
+    ($iter:expr, map($f:expr $(,)?)) => {
+        ::core::iter::Iterator::map($iter, $f)
+    };
+    ($iter:expr, sort($keypath:expr, $compare:expr $(,)?)) => {
+        $crate::composable::pipeline::sort($iter, $keypath, $compare)
+    };
+    ($iter:expr, sort($keypath:expr $(,)?)) => {
+        $crate::composable::pipeline::sort($iter, $keypath, ::core::cmp::Ord::cmp)
     };
-    ($collection:expr, map_keypath($keypath:expr, $transform:expr)) => {
-        $collection.into_iter().map_keypath($keypath, $transform).collect()
+    ($iter:expr, take($n:expr $(,)?)) => {
+        ::core::iter::Iterator::take($iter, $n)
     };
-    ($collection:expr, take($n:expr)) => {
-        $collection.into_iter().take($n).collect()
+    ($iter:expr, skip($n:expr $(,)?)) => {
+        ::core::iter::Iterator::skip($iter, $n)
     };
-    ($collection:expr, skip($n:expr)) => {
-        $collection.into_iter().skip($n).collect()
+    ($iter:expr, join($other:expr, $keypath:expr, $other_keypath:expr $(,)?)) => {
+        $crate::composable::pipeline::join($iter, $other, $keypath, $other_keypath)
     };
+    ($iter:expr, group($keypath:expr $(,)?)) => {
+        $crate::composable::pipeline::group($iter, $keypath)
+    };
+    ($iter:expr, fold($keypath:expr, $init:expr, $f:expr $(,)?)) => {
+        $crate::composable::pipeline::fold($iter, $keypath, $init, $f)
+    };
+    ($iter:expr, $op:ident($($args:tt)*)) => {
+        ::core::compile_error!(::core::concat!(
+            "keypath_pipeline!: invalid operation `",
+            ::core::stringify!($op($($args)*)),
+            "`; expected filter(keypath, predicate), filter(predicate), map(keypath, f), map(f), ",
+            "sort(keypath), sort(keypath, compare), take(n), skip(n), ",
+            "join(other, keypath, other_keypath), group(keypath) or fold(keypath, init, f)"
+        ))
+    };
+}
+
+/// Operations backing `keypath_pipeline!`
+///
+/// Each operation is a generic function so type errors point at the operation's bounds rather
+/// than at the macro expansion.
+#[doc(hidden)]
+pub mod pipeline {
+    use crate::traits::KeyPathRoot;
+    use key_paths_core::KeyPaths;
+    use std::collections::HashMap;
+    use std::hash::Hash;
+    use std::rc::Rc;
+
+    fn value<'a, I, T, V>(keypath: &'a KeyPaths<T, V>, item: &'a I, op: &str) -> &'a V
+    where
+        I: KeyPathRoot<T>,
+    {
+        keypath.get(item.keypath_root()).unwrap_or_else(|| {
+            panic!("KeyPath access failed in keypath_pipeline! `{}`", op)
+        })
+    }
+
+    pub fn filter<I, T, V, F>(iter: impl Iterator<Item = I>, keypath: KeyPaths<T, V>, predicate: F) -> impl Iterator<Item = I>
+    where
+        I: KeyPathRoot<T>,
+        F: Fn(&V) -> bool,
+    {
+        iter.filter(move |item| predicate(value(&keypath, item, "filter")))
+    }
+
+    pub fn map<I, T, V, F, R>(iter: impl Iterator<Item = I>, keypath: KeyPaths<T, V>, f: F) -> impl Iterator<Item = R>
+    where
+        I: KeyPathRoot<T>,
+        F: Fn(&V) -> R,
+    {
+        iter.map(move |item| f(value(&keypath, &item, "map")))
+    }
+
+    pub fn sort<I, T, V, F>(iter: impl Iterator<Item = I>, keypath: KeyPaths<T, V>, compare: F) -> std::vec::IntoIter<I>
+    where
+        I: KeyPathRoot<T>,
+        F: Fn(&V, &V) -> std::cmp::Ordering,
+    {
+        let mut items: Vec<I> = iter.collect();
+        items.sort_by(|a, b| compare(value(&keypath, a, "sort"), value(&keypath, b, "sort")));
+        items.into_iter()
+    }
+
+    pub fn join<'o, I, T, O, U, V>(
+        iter: impl Iterator<Item = I>,
+        other: &'o [O],
+        keypath: KeyPaths<T, V>,
+        other_keypath: KeyPaths<U, V>,
+    ) -> impl Iterator<Item = (I, &'o O)>
+    where
+        I: KeyPathRoot<T> + Clone,
+        O: KeyPathRoot<U>,
+        V: Hash + Eq + Clone,
+    {
+        let mut grouped: HashMap<V, Vec<&'o O>> = HashMap::new();
+        for item in other {
+            grouped.entry(value(&other_keypath, item, "join").clone()).or_default().push(item);
+        }
+        // Shared slices, so looking up an element's matches bumps a count instead of copying them
+        let index: HashMap<V, Rc<[&'o O]>> = grouped.into_iter().map(|(key, matches)| (key, matches.into())).collect();
+        iter.flat_map(move |item| {
+            let matches = index.get(value(&keypath, &item, "join")).map(Rc::clone);
+            let count = matches.as_ref().map_or(0, |matches| matches.len());
+            let mut item = Some(item);
+            (0..count).filter_map(move |position| {
+                let matched = matches.as_ref()?[position];
+                // The last match takes the element itself; only earlier ones clone it
+                let item = if position + 1 == count { item.take() } else { item.clone() }?;
+                Some((item, matched))
+            })
+        })
+    }
+
+    pub fn group<I, T, V>(iter: impl Iterator<Item = I>, keypath: KeyPaths<T, V>) -> HashMap<V, Vec<I>>
+    where
+        I: KeyPathRoot<T>,
+        V: Hash + Eq + Clone,
+    {
+        let mut groups: HashMap<V, Vec<I>> = HashMap::new();
+        for item in iter {
+            let key = value(&keypath, &item, "group").clone();
+            groups.entry(key).or_default().push(item);
+        }
+        groups
+    }
+
+    pub fn fold<I, T, V, F, B>(iter: impl Iterator<Item = I>, keypath: KeyPaths<T, V>, init: B, f: F) -> B
+    where
+        I: KeyPathRoot<T>,
+        F: Fn(B, &V) -> B,
+    {
+        iter.fold(init, |acc, item| f(acc, value(&keypath, &item, "fold")))
+    }
 }
 
 /// Utility functions for common keypath operations
@@ -436,6 +761,8 @@ pub mod utils {
     }
     
     /// Create a keypath predicate that can be reused
+    ///
+    /// See [`crate::predicate::KeyPathPredicate`] for a predicate that can be combined and described.
     pub fn create_keypath_predicate<T, V, F>(
         keypath: KeyPaths<T, V>,
         predicate: F,
@@ -452,6 +779,9 @@ pub mod utils {
     }
     
     /// Combine multiple keypath operations
+    ///
+    /// To use two keypaths as one predicate, group key or sort key, zip them instead with
+    /// [`crate::access::zip_keypaths`].
     pub fn combine_keypath_operations<T, V1, V2, F1, F2, R1, R2>(
         keypath1: KeyPaths<T, V1>,
         operation1: F1,
@@ -0,0 +1,412 @@
+//! Type-erased keypaths for runtime field selection
+//!
+//! `KeyPaths<T, V>` is statically typed, which makes it impossible to keep a heterogeneous list
+//! of fields. `AnyKeyPath<T>` erases the value type while keeping the ability to format,
+//! downcast and (with the `serde` feature) serialize the values it yields. A `KeyPathRegistry<T>`
+//! names these keypaths so fields can be selected by string at runtime.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use rust_prelude_plus::dynamic::{KeyPathRegistry, select_by_names};
+//! use key_paths_derive::Keypath;
+//!
+//! #[derive(Keypath, Debug, Clone)]
+//! struct Person {
+//!     name: String,
+//!     age: u32,
+//! }
+//!
+//! let registry = KeyPathRegistry::new()
+//!     .register("name", Person::name())
+//!     .register("age", Person::age());
+//!
+//! let people = vec![
+//!     Person { name: "Alice".to_string(), age: 30 },
+//!     Person { name: "Bob".to_string(), age: 25 },
+//! ];
+//!
+//! // Columns chosen at runtime, e.g. from a UI
+//! let rows = select_by_names(&people, &registry, &["age", "name"]).unwrap();
+//! let rendered: Vec<Vec<String>> = rows
+//!     .iter()
+//!     .map(|row| row.iter().map(|value| value.to_string()).collect())
+//!     .collect();
+//! assert_eq!(rendered, vec![vec!["30", "Alice"], vec!["25", "Bob"]]);
+//! ```
+
+use crate::error::{KeyPathError, KeyPathResult};
+use key_paths_core::KeyPaths;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+type FormatFn = fn(&dyn Any, &mut fmt::Formatter<'_>) -> fmt::Result;
+
+#[cfg(feature = "serde")]
+type SerializeFn = for<'a> fn(&'a dyn Any) -> &'a dyn erased_serde::Serialize;
+
+/// Object-safe view of a keypath whose value type has been erased
+trait ErasedKeyPath<T> {
+    fn get_any<'a>(&'a self, root: &'a T) -> Option<&'a dyn Any>;
+}
+
+impl<T, V: Any> ErasedKeyPath<T> for KeyPaths<T, V> {
+    fn get_any<'a>(&'a self, root: &'a T) -> Option<&'a dyn Any> {
+        self.get(root).map(|value| value as &dyn Any)
+    }
+}
+
+/// An erased keypath reached through a typed keypath to its root
+struct NestedKeyPath<R, T> {
+    outer: KeyPaths<R, T>,
+    inner: Rc<dyn ErasedKeyPath<T>>,
+}
+
+impl<R, T> ErasedKeyPath<R> for NestedKeyPath<R, T> {
+    fn get_any<'a>(&'a self, root: &'a R) -> Option<&'a dyn Any> {
+        self.outer.get(root).and_then(|value| self.inner.get_any(value))
+    }
+}
+
+fn debug_fmt<V: fmt::Debug + 'static>(value: &dyn Any, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match value.downcast_ref::<V>() {
+        Some(value) => fmt::Debug::fmt(value, f),
+        None => f.write_str("<type mismatch>"),
+    }
+}
+
+fn display_fmt<V: fmt::Display + 'static>(value: &dyn Any, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match value.downcast_ref::<V>() {
+        Some(value) => fmt::Display::fmt(value, f),
+        None => f.write_str("<type mismatch>"),
+    }
+}
+
+#[cfg(feature = "serde")]
+fn as_serialize<V: serde::Serialize + 'static>(value: &dyn Any) -> &dyn erased_serde::Serialize {
+    value
+        .downcast_ref::<V>()
+        .expect("AnyKeyPath value type does not match its serializer")
+}
+
+/// A keypath from `T` to a value whose type is only known at runtime
+pub struct AnyKeyPath<T> {
+    keypath: Rc<dyn ErasedKeyPath<T>>,
+    value_type_id: TypeId,
+    value_type_name: &'static str,
+    debug: FormatFn,
+    display: FormatFn,
+    #[cfg(feature = "serde")]
+    serialize: Option<SerializeFn>,
+}
+
+impl<T: 'static> AnyKeyPath<T> {
+    /// Erase a keypath whose value implements `Debug` and `Display`
+    pub fn new<V>(keypath: KeyPaths<T, V>) -> Self
+    where
+        V: fmt::Debug + fmt::Display + 'static,
+    {
+        Self::erase(keypath, display_fmt::<V>)
+    }
+
+    /// Erase a keypath whose value only implements `Debug`
+    ///
+    /// The `Display` output of the values falls back to their `Debug` output.
+    pub fn from_debug<V>(keypath: KeyPaths<T, V>) -> Self
+    where
+        V: fmt::Debug + 'static,
+    {
+        Self::erase(keypath, debug_fmt::<V>)
+    }
+
+    /// Erase a keypath whose values can also be serialized
+    #[cfg(feature = "serde")]
+    pub fn serializable<V>(keypath: KeyPaths<T, V>) -> Self
+    where
+        V: fmt::Debug + fmt::Display + serde::Serialize + 'static,
+    {
+        let mut erased = Self::new(keypath);
+        erased.serialize = Some(as_serialize::<V>);
+        erased
+    }
+
+    fn erase<V>(keypath: KeyPaths<T, V>, display: FormatFn) -> Self
+    where
+        V: fmt::Debug + 'static,
+    {
+        Self {
+            keypath: Rc::new(keypath),
+            value_type_id: TypeId::of::<V>(),
+            value_type_name: std::any::type_name::<V>(),
+            debug: debug_fmt::<V>,
+            display,
+            #[cfg(feature = "serde")]
+            serialize: None,
+        }
+    }
+}
+
+impl<T> AnyKeyPath<T> {
+    /// Get the type-erased value at this keypath
+    pub fn get<'a>(&'a self, root: &'a T) -> Option<AnyValue<'a>> {
+        self.keypath.get_any(root).map(|value| AnyValue {
+            value,
+            debug: self.debug,
+            display: self.display,
+            #[cfg(feature = "serde")]
+            serialize: self.serialize,
+        })
+    }
+
+    /// Get the value at this keypath if it has type `V`
+    pub fn get_as<'a, V: 'static>(&'a self, root: &'a T) -> Option<&'a V> {
+        self.keypath.get_any(root).and_then(|value| value.downcast_ref::<V>())
+    }
+
+    /// `TypeId` of the erased value type
+    pub fn value_type_id(&self) -> TypeId {
+        self.value_type_id
+    }
+
+    /// Name of the erased value type
+    pub fn value_type_name(&self) -> &'static str {
+        self.value_type_name
+    }
+
+    /// Whether the erased value type is `V`
+    pub fn is<V: 'static>(&self) -> bool {
+        self.value_type_id == TypeId::of::<V>()
+    }
+
+    /// Whether values at this keypath can be serialized
+    #[cfg(feature = "serde")]
+    pub fn is_serializable(&self) -> bool {
+        self.serialize.is_some()
+    }
+
+    /// Lift the keypath to a parent type through a keypath
+    pub fn at<R>(self, keypath: KeyPaths<R, T>) -> AnyKeyPath<R>
+    where
+        T: 'static,
+        R: 'static,
+    {
+        AnyKeyPath {
+            keypath: Rc::new(NestedKeyPath {
+                outer: keypath,
+                inner: self.keypath,
+            }),
+            value_type_id: self.value_type_id,
+            value_type_name: self.value_type_name,
+            debug: self.debug,
+            display: self.display,
+            #[cfg(feature = "serde")]
+            serialize: self.serialize,
+        }
+    }
+}
+
+impl<T> Clone for AnyKeyPath<T> {
+    fn clone(&self) -> Self {
+        Self {
+            keypath: Rc::clone(&self.keypath),
+            value_type_id: self.value_type_id,
+            value_type_name: self.value_type_name,
+            debug: self.debug,
+            display: self.display,
+            #[cfg(feature = "serde")]
+            serialize: self.serialize,
+        }
+    }
+}
+
+impl<T> fmt::Debug for AnyKeyPath<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyKeyPath")
+            .field("value_type", &self.value_type_name)
+            .finish()
+    }
+}
+
+/// A borrowed value obtained through an `AnyKeyPath`
+#[derive(Clone, Copy)]
+pub struct AnyValue<'a> {
+    value: &'a dyn Any,
+    debug: FormatFn,
+    display: FormatFn,
+    #[cfg(feature = "serde")]
+    serialize: Option<SerializeFn>,
+}
+
+impl<'a> AnyValue<'a> {
+    /// The value as `&dyn Any`
+    pub fn as_any(&self) -> &'a dyn Any {
+        self.value
+    }
+
+    /// Downcast the value to a concrete type
+    pub fn downcast_ref<V: 'static>(&self) -> Option<&'a V> {
+        self.value.downcast_ref::<V>()
+    }
+
+    /// Whether the value has type `V`
+    pub fn is<V: 'static>(&self) -> bool {
+        self.value.is::<V>()
+    }
+}
+
+impl fmt::Debug for AnyValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.debug)(self.value, f)
+    }
+}
+
+impl fmt::Display for AnyValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.display)(self.value, f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AnyValue<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.serialize {
+            Some(as_serialize) => erased_serde::serialize(as_serialize(self.value), serializer),
+            None => Err(serde::ser::Error::custom(
+                "value was registered without serde support; use AnyKeyPath::serializable",
+            )),
+        }
+    }
+}
+
+/// Named type-erased keypaths, in registration order
+pub struct KeyPathRegistry<T> {
+    entries: Vec<(String, AnyKeyPath<T>)>,
+    index: HashMap<String, usize>,
+}
+
+impl<T: 'static> KeyPathRegistry<T> {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Register a keypath whose value implements `Debug` and `Display`
+    pub fn register<V>(self, name: impl Into<String>, keypath: KeyPaths<T, V>) -> Self
+    where
+        V: fmt::Debug + fmt::Display + 'static,
+    {
+        self.register_any(name, AnyKeyPath::new(keypath))
+    }
+
+    /// Register an already erased keypath
+    ///
+    /// Registering a name twice replaces the earlier keypath but keeps its position.
+    pub fn register_any(mut self, name: impl Into<String>, keypath: AnyKeyPath<T>) -> Self {
+        self.insert(name, keypath);
+        self
+    }
+
+    /// Insert an erased keypath, returning the one previously registered under `name`
+    pub fn insert(&mut self, name: impl Into<String>, keypath: AnyKeyPath<T>) -> Option<AnyKeyPath<T>> {
+        let name = name.into();
+        match self.index.get(&name) {
+            Some(&position) => Some(std::mem::replace(&mut self.entries[position].1, keypath)),
+            None => {
+                self.index.insert(name.clone(), self.entries.len());
+                self.entries.push((name, keypath));
+                None
+            }
+        }
+    }
+}
+
+impl<T> KeyPathRegistry<T> {
+    /// Look up a keypath by name
+    pub fn get(&self, name: &str) -> Option<&AnyKeyPath<T>> {
+        self.index.get(name).map(|&position| &self.entries[position].1)
+    }
+
+    /// Look up a keypath by name, failing with a descriptive error
+    pub fn require(&self, name: &str) -> KeyPathResult<&AnyKeyPath<T>> {
+        self.get(name).ok_or_else(|| KeyPathError::InvalidAccess {
+            message: format!("unknown field '{}' (known fields: {})", name, self.names().collect::<Vec<_>>().join(", ")),
+        })
+    }
+
+    /// Registered names, in registration order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Registered names and keypaths, in registration order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &AnyKeyPath<T>)> {
+        self.entries.iter().map(|(name, keypath)| (name.as_str(), keypath))
+    }
+
+    /// Number of registered keypaths
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the registry is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the value of a named field on an element
+    pub fn get_value<'a>(&'a self, item: &'a T, name: &str) -> KeyPathResult<AnyValue<'a>> {
+        let keypath = self.require(name)?;
+        keypath.get(item).ok_or_else(|| KeyPathError::InvalidAccess {
+            message: format!("KeyPath access failed for field '{}'", name),
+        })
+    }
+}
+
+impl<T: 'static> Default for KeyPathRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collect type-erased values from a keypath
+pub fn collect_any_keypath<'a, T>(
+    collection: &'a [T],
+    keypath: &'a AnyKeyPath<T>,
+) -> KeyPathResult<Vec<AnyValue<'a>>> {
+    let mut result = Vec::with_capacity(collection.len());
+    for item in collection {
+        let value = keypath.get(item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        result.push(value);
+    }
+    Ok(result)
+}
+
+/// Collect one row of type-erased values per element for the named fields
+pub fn select_by_names<'a, T>(
+    collection: &'a [T],
+    registry: &'a KeyPathRegistry<T>,
+    names: &[&str],
+) -> KeyPathResult<Vec<Vec<AnyValue<'a>>>> {
+    let keypaths = names
+        .iter()
+        .map(|name| registry.require(name))
+        .collect::<KeyPathResult<Vec<_>>>()?;
+
+    let mut rows = Vec::with_capacity(collection.len());
+    for item in collection {
+        let mut row = Vec::with_capacity(keypaths.len());
+        for (keypath, name) in keypaths.iter().zip(names) {
+            let value = keypath.get(item).ok_or_else(|| KeyPathError::InvalidAccess {
+                message: format!("KeyPath access failed for field '{}'", name),
+            })?;
+            row.push(value);
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
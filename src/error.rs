@@ -1,5 +1,29 @@
 //! Error types and handling for keypath operations
+//!
+//! `KeyPathError` is `Clone + PartialEq` so results can be compared in tests and shared between
+//! threads. Errors raised by other libraries are kept as an `ErrorSource`, a cheaply cloneable
+//! handle exposed through `std::error::Error::source`, so `anyhow` and `eyre` reports still show
+//! the full cause chain. Variants that predate sources get one attached with `with_source` or
+//! constructors such as `runtime_with_source`. Every variant also has a stable `code()` for logs
+//! and metrics.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use rust_prelude_plus::prelude::*;
+//! use std::error::Error;
+//!
+//! let io = std::io::Error::new(std::io::ErrorKind::NotFound, "people.json");
+//! let error = KeyPathError::from(io);
+//!
+//! assert_eq!(error.code(), "io_error");
+//! assert_eq!(error.to_string(), "I/O error: people.json");
+//! let source = error.source().unwrap().downcast_ref::<std::io::Error>().unwrap();
+//! assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+//! ```
 
+use std::error::Error as StdError;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Errors that can occur during keypath operations
@@ -36,12 +60,276 @@ pub enum KeyPathError {
     /// Serialization/deserialization failed
     #[error("Serialization error: {message}")]
     SerializationError { message: String },
+
+    /// Reading or writing failed
+    #[error("I/O error: {message}")]
+    Io { message: String, source: Option<ErrorSource> },
+
+    /// Query parsing or compilation failed
+    #[error("Query error at position {position}: {message}")]
+    QueryError { message: String, position: usize },
+
+    /// A user closure passed to a `try_*` operation returned an error
+    ///
+    /// The closure's own error is kept as `source`, so it can be downcast or walked with
+    /// `std::error::Error::source`.
+    #[error("Closure failed at index {index}: {message}")]
+    ClosureError { index: usize, message: String, source: ErrorSource },
+
+    /// Several errors collected by an operation running in `ErrorMode::CollectAll`
+    #[error("{} errors occurred", errors.len())]
+    Multiple { errors: Vec<KeyPathError> },
+
+    /// Another error together with the error that caused it
+    ///
+    /// Built by `with_source` and the `*_with_source` constructors. `Display` and `code()` are
+    /// those of the wrapped error; match on `kind()` to see its variant.
+    #[error("{error}")]
+    WithSource { error: Box<KeyPathError>, source: ErrorSource },
+}
+
+impl KeyPathError {
+    /// Stable, machine-readable identifier of the error kind
+    ///
+    /// Codes never change between releases, unlike the `Display` messages.
+    pub fn code(&self) -> &'static str {
+        match self {
+            KeyPathError::InvalidAccess { .. } => "invalid_access",
+            KeyPathError::TypeMismatch { .. } => "type_mismatch",
+            KeyPathError::RuntimeFailure { .. } => "runtime_failure",
+            KeyPathError::CollectionError { .. } => "collection_error",
+            KeyPathError::AsyncError { .. } => "async_error",
+            KeyPathError::ParallelError { .. } => "parallel_error",
+            KeyPathError::NetworkError { .. } => "network_error",
+            KeyPathError::SerializationError { .. } => "serialization_error",
+            KeyPathError::Io { .. } => "io_error",
+            KeyPathError::QueryError { .. } => "query_error",
+            KeyPathError::ClosureError { .. } => "closure_error",
+            KeyPathError::Multiple { .. } => "multiple",
+            KeyPathError::WithSource { error, .. } => error.code(),
+        }
+    }
+
+    /// The error without any attached source, for matching on its variant
+    pub fn kind(&self) -> &KeyPathError {
+        match self {
+            KeyPathError::WithSource { error, .. } => error.kind(),
+            other => other,
+        }
+    }
+
+    /// Attach the error that caused this one, exposed through `std::error::Error::source`
+    pub fn with_source<E>(self, source: E) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        KeyPathError::WithSource {
+            error: Box::new(self),
+            source: ErrorSource::new(source),
+        }
+    }
+
+    /// A `RuntimeFailure` keeping `error` as its source
+    pub fn runtime_with_source<E>(error: E) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        KeyPathError::RuntimeFailure { message: error.to_string() }.with_source(error)
+    }
+
+    /// A `ParallelError` keeping `error` as its source
+    pub fn parallel_with_source<E>(error: E) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        KeyPathError::ParallelError { message: error.to_string() }.with_source(error)
+    }
+
+    /// A `NetworkError` keeping `error` as its source
+    pub fn network_with_source<E>(error: E) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        KeyPathError::NetworkError { message: error.to_string() }.with_source(error)
+    }
+
+    /// A `SerializationError` keeping `error` as its source
+    ///
+    /// Use this for format-specific errors such as `serde_json::Error`.
+    pub fn serialization<E>(error: E) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        KeyPathError::SerializationError { message: error.to_string() }.with_source(error)
+    }
+}
+
+/// A shared handle to the error that caused a `KeyPathError`
+///
+/// Wraps the original error in an `Arc` so `KeyPathError` stays `Clone`. It is not itself an error:
+/// `KeyPathError::source` returns the wrapped error, so `downcast_ref` and chain walkers such as
+/// `anyhow`'s `chain()` see the original error directly. Two sources compare equal
+/// only when they are the same error, i.e. one is a clone of the other; distinct errors never do,
+/// even if they render the same message.
+#[derive(Clone)]
+pub struct ErrorSource(Arc<dyn StdError + Send + Sync + 'static>);
+
+impl ErrorSource {
+    /// Wrap an error
+    pub fn new<E>(error: E) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        Self(Arc::new(error))
+    }
+
+    /// The wrapped error
+    pub fn get(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        &*self.0
+    }
+
+    /// The wrapped error, if it has type `E`
+    pub fn downcast_ref<E: StdError + 'static>(&self) -> Option<&E> {
+        self.0.downcast_ref::<E>()
+    }
+}
+
+impl std::fmt::Debug for ErrorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl std::fmt::Display for ErrorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl std::ops::Deref for ErrorSource {
+    type Target = dyn StdError + Send + Sync + 'static;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl PartialEq for ErrorSource {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl From<std::io::Error> for KeyPathError {
+    fn from(error: std::io::Error) -> Self {
+        KeyPathError::Io {
+            message: error.to_string(),
+            source: Some(ErrorSource::new(error)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde::de::value::Error> for KeyPathError {
+    fn from(error: serde::de::value::Error) -> Self {
+        KeyPathError::serialization(error)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<erased_serde::Error> for KeyPathError {
+    fn from(error: erased_serde::Error) -> Self {
+        KeyPathError::serialization(error)
+    }
+}
+
+/// Lets `KeyPathError` be the error type of a `Serialize` implementation
+#[cfg(feature = "serde")]
+impl serde::ser::Error for KeyPathError {
+    fn custom<T: std::fmt::Display>(message: T) -> Self {
+        KeyPathError::SerializationError {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Lets `KeyPathError` be the error type of a `Deserialize` implementation
+#[cfg(feature = "serde")]
+impl serde::de::Error for KeyPathError {
+    fn custom<T: std::fmt::Display>(message: T) -> Self {
+        KeyPathError::SerializationError {
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl From<rayon::ThreadPoolBuildError> for KeyPathError {
+    fn from(error: rayon::ThreadPoolBuildError) -> Self {
+        KeyPathError::parallel_with_source(error)
+    }
+}
+
+/// How `try_*` operations react to a failing element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorMode {
+    /// Stop at the first error and return it
+    #[default]
+    FailFast,
+    /// Process every element, then return every error as `KeyPathError::Multiple`
+    CollectAll,
+}
+
+/// Records errors for a `try_*` operation according to its `ErrorMode`
+pub(crate) struct ErrorCollector {
+    mode: ErrorMode,
+    errors: Vec<KeyPathError>,
+}
+
+impl ErrorCollector {
+    pub(crate) fn new(mode: ErrorMode) -> Self {
+        Self { mode, errors: Vec::new() }
+    }
+
+    /// Record an error, returning it straight away when failing fast
+    pub(crate) fn record(&mut self, error: KeyPathError) -> KeyPathResult<()> {
+        match self.mode {
+            ErrorMode::FailFast => Err(error),
+            ErrorMode::CollectAll => {
+                self.errors.push(error);
+                Ok(())
+            }
+        }
+    }
+
+    /// The operation's result, or every recorded error
+    pub(crate) fn finish<T>(self, value: T) -> KeyPathResult<T> {
+        if self.errors.is_empty() {
+            Ok(value)
+        } else {
+            Err(KeyPathError::Multiple { errors: self.errors })
+        }
+    }
+}
+
+/// Wrap the error of a user closure applied to the element at `index`
+pub(crate) fn closure_error<E>(index: usize, error: E) -> KeyPathError
+where
+    E: StdError + Send + Sync + 'static,
+{
+    KeyPathError::ClosureError {
+        index,
+        message: error.to_string(),
+        source: ErrorSource::new(error),
+    }
 }
 
 /// Result type for keypath operations
 pub type KeyPathResult<T> = Result<T, KeyPathError>;
 
 /// Extension trait for converting standard errors to KeyPathError
+///
+/// Only the message is kept; use `KeyPathError::runtime_with_source` to keep the error itself.
 pub trait IntoKeyPathError {
     fn into_keypath_error(self) -> KeyPathError;
 }
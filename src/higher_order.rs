@@ -1,6 +1,20 @@
 //! Higher-order functions for keypath operations
+//!
+//! Operations that read one value per element take any `KeyPathAccess` accessor: a `KeyPaths`, a
+//! `ZippedKeyPath`, a `ComputedKeyPath` or a `CachedKeyPath`. That covers the map, filter, fold,
+//! scan, find, group, sort, partition, dedup, chunk, batch, merge and zip operations and their
+//! `try_` forms, as well as the condition of `update_where`. Writes go through a `KeyPaths`, as in
+//! `for_each_keypath_mut`, and the `flat` operations take a `KeyPaths` to a collection.
+//!
+//! `filter_by_keypath`, `find_by_keypath`, `group_by_keypath`, `sort_by_keypath`,
+//! `partition_by_keypath` and `dedup_by_keypath` keep their type parameters in their earlier
+//! order, with `V` the accessor's `Value`; the accessor type comes last.
 
-use crate::error::{KeyPathResult, KeyPathError};
+use crate::access::{AccessOutput, KeyPathAccess, OwnedKeyPathAccess};
+use crate::collections::KeepOccurrence;
+use crate::error::{closure_error, ErrorCollector, ErrorMode, KeyPathResult, KeyPathError};
+use crate::traits::KeyPathRoot;
+use crate::traversal::KeyPathTraversal;
 use key_paths_core::KeyPaths;
 use std::collections::HashMap;
 
@@ -22,30 +36,34 @@ use std::collections::HashMap;
 /// let result = map_keypath(person, Person::name(), |name| name.to_uppercase()).unwrap();
 /// assert_eq!(result, "ALICE");
 /// ```
-pub fn map_keypath<T, V, F, R>(
-    data: T,
-    keypath: KeyPaths<T, V>,
+pub fn map_keypath<I, T, K, F, R>(
+    data: I,
+    keypath: K,
     f: F,
 ) -> KeyPathResult<R>
 where
-    F: FnOnce(&V) -> R,
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> FnOnce(AccessOutput<'a, K, T>) -> R,
 {
-    let value = keypath.get(&data).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+    let value = keypath.access(data.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
     Ok(f(value))
 }
 
 /// Transform values at a specific keypath for collections
-pub fn map_keypath_collection<T, V, F, R>(
-    collection: &[T],
-    keypath: KeyPaths<T, V>,
+pub fn map_keypath_collection<I, T, K, F, R>(
+    collection: &[I],
+    keypath: K,
     f: F,
 ) -> KeyPathResult<Vec<R>>
 where
-    F: Fn(&V) -> R,
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>) -> R,
 {
     let mut result = Vec::new();
     for item in collection {
-        let value = keypath.get(item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
         result.push(f(value));
     }
     Ok(result)
@@ -73,17 +91,19 @@ where
 /// assert_eq!(young_people.len(), 1);
 /// assert_eq!(young_people[0].name, "Bob");
 /// ```
-pub fn filter_by_keypath<T, V, F>(
-    collection: Vec<T>,
-    keypath: KeyPaths<T, V>,
+pub fn filter_by_keypath<I, T, V, F, K>(
+    collection: Vec<I>,
+    keypath: K,
     predicate: F,
-) -> KeyPathResult<Vec<T>>
+) -> KeyPathResult<Vec<I>>
 where
-    F: Fn(&V) -> bool,
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T, Value = V>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool,
 {
     let mut result = Vec::new();
     for item in collection {
-        let value = keypath.get(&item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
         if predicate(value) {
             result.push(item);
         }
@@ -112,23 +132,342 @@ where
 /// let total_age = fold_keypath(people, Person::age(), 0, |acc, &age| acc + age).unwrap();
 /// assert_eq!(total_age, 55);
 /// ```
-pub fn fold_keypath<T, V, F, B>(
-    collection: Vec<T>,
-    keypath: KeyPaths<T, V>,
+pub fn fold_keypath<I, T, K, F, B>(
+    collection: Vec<I>,
+    keypath: K,
     init: B,
     f: F,
 ) -> KeyPathResult<B>
 where
-    F: Fn(B, &V) -> B,
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> Fn(B, AccessOutput<'a, K, T>) -> B,
 {
     let mut acc = init;
     for item in collection {
-        let value = keypath.get(&item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
         acc = f(acc, value);
     }
     Ok(acc)
 }
 
+/// Accumulate values from keypaths, returning the accumulator after every element
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Reading {
+///     celsius: f64,
+/// }
+/// 
+/// let readings = vec![Reading { celsius: 20.0 }, Reading { celsius: 22.0 }, Reading { celsius: 21.0 }];
+/// // Exponential moving average
+/// let smoothed = scan_keypath(&readings, Reading::celsius(), None, |average: Option<f64>, &value| {
+///     Some(average.map_or(value, |average| 0.5 * average + 0.5 * value))
+/// }).unwrap();
+/// assert_eq!(smoothed, vec![Some(20.0), Some(21.0), Some(21.0)]);
+/// ```
+pub fn scan_keypath<I, T, K, F, B>(
+    collection: &[I],
+    keypath: K,
+    init: B,
+    f: F,
+) -> KeyPathResult<Vec<B>>
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    B: Clone,
+    F: for<'a> Fn(B, AccessOutput<'a, K, T>) -> B,
+{
+    let mut result = Vec::with_capacity(collection.len());
+    let mut acc = init;
+    for item in collection {
+        let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        acc = f(acc, value);
+        result.push(acc.clone());
+    }
+    Ok(result)
+}
+
+/// Transform the value at a keypath with a fallible closure
+/// 
+/// An error returned by the closure is wrapped in `KeyPathError::ClosureError`.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Record {
+///     raw_age: String,
+/// }
+/// 
+/// let record = Record { raw_age: "42".to_string() };
+/// let age: u32 = try_map_keypath(record, Record::raw_age(), |raw| raw.parse::<u32>()).unwrap();
+/// assert_eq!(age, 42);
+/// ```
+pub fn try_map_keypath<I, T, K, F, R, E>(
+    data: I,
+    keypath: K,
+    f: F,
+) -> KeyPathResult<R>
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> FnOnce(AccessOutput<'a, K, T>) -> Result<R, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let value = keypath.access(data.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+    f(value).map_err(|error| closure_error(0, error))
+}
+
+/// Transform values at a keypath for collections with a fallible closure
+/// 
+/// With `ErrorMode::FailFast` the first error is returned; with `ErrorMode::CollectAll` every
+/// element is processed and all errors are returned together as `KeyPathError::Multiple`.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Record {
+///     raw_age: String,
+/// }
+/// 
+/// let records = vec![
+///     Record { raw_age: "42".to_string() },
+///     Record { raw_age: "old".to_string() },
+///     Record { raw_age: "-1".to_string() },
+/// ];
+/// 
+/// let error = try_map_keypath_collection(&records, Record::raw_age(), |raw| raw.parse::<u32>(), ErrorMode::FailFast).unwrap_err();
+/// assert!(matches!(error, KeyPathError::ClosureError { index: 1, .. }));
+/// 
+/// match try_map_keypath_collection(&records, Record::raw_age(), |raw| raw.parse::<u32>(), ErrorMode::CollectAll) {
+///     Err(KeyPathError::Multiple { errors }) => assert_eq!(errors.len(), 2),
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+pub fn try_map_keypath_collection<I, T, K, F, R, E>(
+    collection: &[I],
+    keypath: K,
+    f: F,
+    mode: ErrorMode,
+) -> KeyPathResult<Vec<R>>
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>) -> Result<R, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut errors = ErrorCollector::new(mode);
+    let mut result = Vec::with_capacity(collection.len());
+    for (index, item) in collection.iter().enumerate() {
+        let outcome = keypath
+            .access(item.keypath_root())
+            .ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })
+            .and_then(|value| f(value).map_err(|error| closure_error(index, error)));
+        match outcome {
+            Ok(value) => result.push(value),
+            Err(error) => errors.record(error)?,
+        }
+    }
+    errors.finish(result)
+}
+
+/// Filter collections with a fallible keypath predicate
+/// 
+/// Elements whose predicate fails are dropped; `mode` selects whether the first failure is
+/// returned straight away or all failures are collected.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Record {
+///     raw_age: String,
+/// }
+/// 
+/// let records = vec![Record { raw_age: "42".to_string() }, Record { raw_age: "17".to_string() }];
+/// let adults = try_filter_by_keypath(
+///     records,
+///     Record::raw_age(),
+///     |raw| raw.parse::<u32>().map(|age| age >= 18),
+///     ErrorMode::FailFast,
+/// ).unwrap();
+/// assert_eq!(adults.len(), 1);
+/// ```
+pub fn try_filter_by_keypath<I, T, K, F, E>(
+    collection: Vec<I>,
+    keypath: K,
+    predicate: F,
+    mode: ErrorMode,
+) -> KeyPathResult<Vec<I>>
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>) -> Result<bool, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut errors = ErrorCollector::new(mode);
+    let mut result = Vec::new();
+    for (index, item) in collection.into_iter().enumerate() {
+        let outcome = keypath
+            .access(item.keypath_root())
+            .ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })
+            .and_then(|value| predicate(value).map_err(|error| closure_error(index, error)));
+        match outcome {
+            Ok(true) => result.push(item),
+            Ok(false) => {}
+            Err(error) => errors.record(error)?,
+        }
+    }
+    errors.finish(result)
+}
+
+/// Accumulate values from keypaths with a fallible closure, stopping at the first error
+/// 
+/// The accumulator is moved into each step, so a fold cannot continue past a failure.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Line {
+///     quantity: u8,
+/// }
+/// 
+/// let lines = vec![Line { quantity: 200 }, Line { quantity: 100 }];
+/// let total = try_fold_keypath(lines, Line::quantity(), 0u8, |acc, &quantity| {
+///     u8::try_from(u16::from(acc) + u16::from(quantity))
+/// });
+/// assert!(matches!(total, Err(KeyPathError::ClosureError { index: 1, .. })));
+/// ```
+pub fn try_fold_keypath<I, T, K, F, B, E>(
+    collection: Vec<I>,
+    keypath: K,
+    init: B,
+    f: F,
+) -> KeyPathResult<B>
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> Fn(B, AccessOutput<'a, K, T>) -> Result<B, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut acc = init;
+    for (index, item) in collection.into_iter().enumerate() {
+        let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        acc = f(acc, value).map_err(|error| closure_error(index, error))?;
+    }
+    Ok(acc)
+}
+
+/// Transform values at a keypath, keeping going past elements that fail
+/// 
+/// Returns the results for the elements that could be read, in order, and the index and error
+/// of every element where the keypath was absent, e.g. a failable keypath over an `Option` field.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Contact {
+///     email: Option<String>,
+/// }
+/// 
+/// let contacts = vec![
+///     Contact { email: Some("alice@example.com".to_string()) },
+///     Contact { email: None },
+/// ];
+/// 
+/// let (domains, errors) = map_keypath_collect_errors(&contacts, Contact::email(), |email| {
+///     email.split('@').nth(1).unwrap_or_default().to_string()
+/// });
+/// assert_eq!(domains, vec!["example.com"]);
+/// assert_eq!(errors[0].0, 1);
+/// 
+/// let mut report = KeyPathReport::new(contacts.len());
+/// report.record("email", &errors);
+/// assert_eq!(report.failures("email"), 1);
+/// ```
+pub fn map_keypath_collect_errors<I, T, K, F, R>(
+    collection: &[I],
+    keypath: K,
+    f: F,
+) -> (Vec<R>, Vec<(usize, KeyPathError)>)
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>) -> R,
+{
+    try_map_keypath_collect_errors(collection, keypath, |value| Ok::<R, std::convert::Infallible>(f(value)))
+}
+
+/// Transform values at a keypath with a fallible closure, keeping going past elements that fail
+/// 
+/// Closure errors are reported as `KeyPathError::ClosureError` alongside keypath access failures.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Record {
+///     raw_age: String,
+/// }
+/// 
+/// let records = vec![Record { raw_age: "42".to_string() }, Record { raw_age: "old".to_string() }];
+/// let (ages, errors) = try_map_keypath_collect_errors(&records, Record::raw_age(), |raw| raw.parse::<u32>());
+/// assert_eq!(ages, vec![42]);
+/// assert!(matches!(errors[0], (1, KeyPathError::ClosureError { index: 1, .. })));
+/// ```
+pub fn try_map_keypath_collect_errors<I, T, K, F, R, E>(
+    collection: &[I],
+    keypath: K,
+    f: F,
+) -> (Vec<R>, Vec<(usize, KeyPathError)>)
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>) -> Result<R, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut results = Vec::with_capacity(collection.len());
+    let mut errors = Vec::new();
+    for (index, item) in collection.iter().enumerate() {
+        let outcome = keypath
+            .access(item.keypath_root())
+            .ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })
+            .and_then(|value| f(value).map_err(|error| closure_error(index, error)));
+        match outcome {
+            Ok(value) => results.push(value),
+            Err(error) => errors.push((index, error)),
+        }
+    }
+    (results, errors)
+}
+
 /// Find elements matching keypath conditions
 /// 
 /// # Examples
@@ -151,16 +490,18 @@ where
 /// assert!(found.is_some());
 /// assert_eq!(found.unwrap().name, "Alice");
 /// ```
-pub fn find_by_keypath<T, V, F>(
-    collection: Vec<T>,
-    keypath: KeyPaths<T, V>,
+pub fn find_by_keypath<I, T, V, F, K>(
+    collection: Vec<I>,
+    keypath: K,
     predicate: F,
-) -> KeyPathResult<Option<T>>
+) -> KeyPathResult<Option<I>>
 where
-    F: Fn(&V) -> bool,
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T, Value = V>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool,
 {
     for item in collection {
-        let value = keypath.get(&item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
         if predicate(value) {
             return Ok(Some(item));
         }
@@ -195,21 +536,22 @@ where
 /// assert_eq!(grouped["Engineering"].len(), 2);
 /// assert_eq!(grouped["Marketing"].len(), 1);
 /// ```
-pub fn group_by_keypath<T, V, F, K>(
-    collection: &[T],
-    keypath: KeyPaths<T, V>,
+pub fn group_by_keypath<I, T, V, F, K, A>(
+    collection: &[I],
+    keypath: A,
     key_fn: F,
-) -> KeyPathResult<HashMap<K, Vec<T>>>
+) -> KeyPathResult<HashMap<K, Vec<I>>>
 where
-    T: Clone,
-    F: Fn(&V) -> K,
+    I: KeyPathRoot<T> + Clone,
+    A: KeyPathAccess<T, Value = V>,
+    F: for<'a> Fn(AccessOutput<'a, A, T>) -> K,
     K: std::hash::Hash + Eq,
 {
-    let mut groups: HashMap<K, Vec<T>> = HashMap::new();
+    let mut groups: HashMap<K, Vec<I>> = HashMap::new();
     for item in collection {
-        let value = keypath.get(item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
         let key = key_fn(value);
-        groups.entry(key).or_insert_with(Vec::new).push(item.clone());
+        groups.entry(key).or_default().push(item.clone());
     }
     Ok(groups)
 }
@@ -239,22 +581,91 @@ where
 /// assert_eq!(people[1].age, 30);
 /// assert_eq!(people[2].age, 35);
 /// ```
-pub fn sort_by_keypath<T, V, F>(
-    collection: &mut [T],
-    keypath: KeyPaths<T, V>,
+pub fn sort_by_keypath<I, T, V, F, K>(
+    collection: &mut [I],
+    keypath: K,
     compare: F,
 ) -> KeyPathResult<()>
 where
-    F: Fn(&V, &V) -> std::cmp::Ordering,
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T, Value = V>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>, AccessOutput<'a, K, T>) -> std::cmp::Ordering,
 {
     collection.sort_by(|a, b| {
-        let val_a = keypath.get(a).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() }).unwrap();
-        let val_b = keypath.get(b).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() }).unwrap();
+        let val_a = keypath.access(a.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() }).unwrap();
+        let val_b = keypath.access(b.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() }).unwrap();
         compare(val_a, val_b)
     });
     Ok(())
 }
 
+/// Sort collections by keypath values, reading each value once
+///
+/// `sort_by_keypath` reads both values on every comparison, O(n log n) reads in all. This reads
+/// each element's value once, sorts the values with their indices and then moves the elements
+/// into place, like `slice::sort_by_cached_key`. Worth it for computed or deeply nested keypaths.
+/// The values are kept owned, so a plain keypath's value is cloned once per element, and the
+/// comparison receives `&V` just like `sort_by_keypath`'s. The sort is stable, and the collection
+/// is left untouched if any value is absent.
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Person {
+///     first: String,
+///     last: String,
+/// }
+///
+/// let mut people = vec![
+///     Person { first: "Grace".to_string(), last: "Hopper".to_string() },
+///     Person { first: "Ada".to_string(), last: "Lovelace".to_string() },
+///     Person { first: "Alan".to_string(), last: "Turing".to_string() },
+/// ];
+///
+/// let initials = computed_keypath(|person: &Person| format!("{}{}", &person.first[..1], &person.last[..1]));
+/// sort_by_cached_keypath(&mut people, initials, |a, b| a.cmp(b)).unwrap();
+/// let firsts: Vec<&str> = people.iter().map(|person| person.first.as_str()).collect();
+/// assert_eq!(firsts, vec!["Ada", "Alan", "Grace"]);
+/// ```
+pub fn sort_by_cached_keypath<I, T, K, F>(
+    collection: &mut [I],
+    keypath: K,
+    compare: F,
+) -> KeyPathResult<()>
+where
+    I: KeyPathRoot<T>,
+    K: OwnedKeyPathAccess<T>,
+    F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering,
+{
+    let mut order: Vec<usize> = {
+        let mut decorated = collection
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let value = keypath.access_owned(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+                Ok((value, index))
+            })
+            .collect::<KeyPathResult<Vec<_>>>()?;
+        decorated.sort_by(|(a, _), (b, _)| compare(a, b));
+        decorated.into_iter().map(|(_, index)| index).collect()
+    };
+    // Element `order[i]` belongs at `i`; earlier swaps may have moved it, so follow the chain of
+    // indices already placed to find where it is now
+    for i in 0..order.len() {
+        let mut index = order[i];
+        while index < i {
+            index = order[index];
+        }
+        order[i] = index;
+        collection.swap(i, index);
+    }
+    Ok(())
+}
+
 /// Extract values from keypaths into collections
 /// 
 /// # Examples
@@ -276,17 +687,18 @@ where
 /// let ages = collect_keypath(people, Person::age()).unwrap();
 /// assert_eq!(ages, vec![30, 25]);
 /// ```
-pub fn collect_keypath<T, V>(
-    collection: Vec<T>,
-    keypath: KeyPaths<T, V>,
-) -> KeyPathResult<Vec<V>>
+pub fn collect_keypath<I, T, K>(
+    collection: Vec<I>,
+    keypath: K,
+) -> KeyPathResult<Vec<K::Value>>
 where
-    V: Clone,
+    I: KeyPathRoot<T>,
+    K: OwnedKeyPathAccess<T>,
 {
     let mut result = Vec::new();
     for item in collection {
-        let value = keypath.get(&item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
-        result.push(value.clone());
+        let value = keypath.access_owned(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        result.push(value);
     }
     Ok(result)
 }
@@ -316,19 +728,21 @@ where
 /// assert_eq!(old.len(), 2);
 /// assert_eq!(young[0].name, "Bob");
 /// ```
-pub fn partition_by_keypath<T, V, F>(
-    collection: Vec<T>,
-    keypath: KeyPaths<T, V>,
+pub fn partition_by_keypath<I, T, V, F, K>(
+    collection: Vec<I>,
+    keypath: K,
     predicate: F,
-) -> KeyPathResult<(Vec<T>, Vec<T>)>
+) -> KeyPathResult<(Vec<I>, Vec<I>)>
 where
-    F: Fn(&V) -> bool,
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T, Value = V>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool,
 {
     let mut left = Vec::new();
     let mut right = Vec::new();
     
     for item in collection {
-        let value = keypath.get(&item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
         if predicate(value) {
             left.push(item);
         } else {
@@ -339,6 +753,295 @@ where
     Ok((left, right))
 }
 
+/// Remove elements with a repeated keypath value, keeping the first or last occurrence
+/// 
+/// The kept elements stay in their original relative order.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Event {
+///     user_id: u32,
+///     action: String,
+/// }
+/// 
+/// let events = vec![
+///     Event { user_id: 1, action: "login".to_string() },
+///     Event { user_id: 2, action: "login".to_string() },
+///     Event { user_id: 1, action: "logout".to_string() },
+/// ];
+/// 
+/// let latest = dedup_by_keypath(events, Event::user_id(), KeepOccurrence::Last).unwrap();
+/// let actions: Vec<&str> = latest.iter().map(|event| event.action.as_str()).collect();
+/// assert_eq!(actions, vec!["login", "logout"]);
+/// ```
+pub fn dedup_by_keypath<I, T, V, K>(
+    collection: Vec<I>,
+    keypath: K,
+    keep: KeepOccurrence,
+) -> KeyPathResult<Vec<I>>
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T, Value = V>,
+    for<'a> AccessOutput<'a, K, T>: std::hash::Hash + Eq,
+{
+    let mut kept: HashMap<AccessOutput<'_, K, T>, usize> = HashMap::new();
+    for (index, item) in collection.iter().enumerate() {
+        let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        match keep {
+            KeepOccurrence::First => {
+                kept.entry(value).or_insert(index);
+            }
+            KeepOccurrence::Last => {
+                kept.insert(value, index);
+            }
+        }
+    }
+    
+    let mut keep_index = vec![false; collection.len()];
+    for index in kept.into_values() {
+        keep_index[index] = true;
+    }
+    
+    Ok(collection
+        .into_iter()
+        .zip(keep_index)
+        .filter_map(|(item, keep)| keep.then_some(item))
+        .collect())
+}
+
+/// Split a collection into runs of consecutive elements with equal keypath values
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Row {
+///     customer_id: u32,
+///     amount: f64,
+/// }
+/// 
+/// let rows = vec![
+///     Row { customer_id: 1, amount: 10.0 },
+///     Row { customer_id: 1, amount: 5.0 },
+///     Row { customer_id: 2, amount: 7.5 },
+///     Row { customer_id: 1, amount: 2.0 },
+/// ];
+/// 
+/// let chunks = chunk_by_keypath(rows, Row::customer_id()).unwrap();
+/// let sizes: Vec<usize> = chunks.iter().map(|chunk| chunk.len()).collect();
+/// assert_eq!(sizes, vec![2, 1, 1]);
+/// ```
+pub fn chunk_by_keypath<I, T, K>(
+    collection: Vec<I>,
+    keypath: K,
+) -> KeyPathResult<Vec<Vec<I>>>
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    for<'a> AccessOutput<'a, K, T>: PartialEq,
+{
+    let mut chunks: Vec<Vec<I>> = Vec::new();
+    for item in collection {
+        let same_run = {
+            let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+            match chunks.last().and_then(|chunk| chunk.last()) {
+                Some(previous) => keypath.access(previous.keypath_root()) == Some(value),
+                None => false,
+            }
+        };
+        match chunks.last_mut() {
+            Some(chunk) if same_run => chunk.push(item),
+            _ => chunks.push(vec![item]),
+        }
+    }
+    Ok(chunks)
+}
+
+/// Split a collection into batches of at most `max_size` elements without splitting a group
+/// 
+/// Elements sharing a keypath value always land in the same batch, even when they are not
+/// adjacent; groups are packed in order of first appearance. A group larger than `max_size`
+/// gets a batch of its own.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Row {
+///     customer_id: u32,
+///     amount: f64,
+/// }
+/// 
+/// let rows = vec![
+///     Row { customer_id: 1, amount: 10.0 },
+///     Row { customer_id: 2, amount: 7.5 },
+///     Row { customer_id: 1, amount: 5.0 },
+///     Row { customer_id: 3, amount: 1.0 },
+/// ];
+/// 
+/// let batches = batched_by_keypath(rows, Row::customer_id(), 3).unwrap();
+/// let ids: Vec<Vec<u32>> = batches
+///     .iter()
+///     .map(|batch| batch.iter().map(|row| row.customer_id).collect())
+///     .collect();
+/// assert_eq!(ids, vec![vec![1, 1, 2], vec![3]]);
+/// ```
+pub fn batched_by_keypath<I, T, K>(
+    collection: Vec<I>,
+    keypath: K,
+    max_size: usize,
+) -> KeyPathResult<Vec<Vec<I>>>
+where
+    I: KeyPathRoot<T>,
+    K: OwnedKeyPathAccess<T>,
+    K::Value: std::hash::Hash + Eq,
+{
+    if max_size == 0 {
+        return Err(KeyPathError::CollectionError {
+            message: "Batch size must be greater than 0".to_string(),
+        });
+    }
+    
+    let mut groups: Vec<Vec<I>> = Vec::new();
+    let mut positions: HashMap<K::Value, usize> = HashMap::new();
+    for item in collection {
+        let value = keypath.access_owned(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        match positions.get(&value) {
+            Some(&position) => groups[position].push(item),
+            None => {
+                positions.insert(value, groups.len());
+                groups.push(vec![item]);
+            }
+        }
+    }
+    
+    let mut batches: Vec<Vec<I>> = Vec::new();
+    for group in groups {
+        match batches.last_mut() {
+            Some(batch) if batch.len() + group.len() <= max_size => batch.extend(group),
+            _ => batches.push(group),
+        }
+    }
+    Ok(batches)
+}
+
+/// Merge collections that are each sorted by a keypath into one sorted collection
+/// 
+/// This is a k-way merge using a binary heap, so it runs in O(n log k) for `k` inputs. The
+/// merge is stable: equal values keep their order within an input, and earlier inputs come first.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Event {
+///     timestamp: u64,
+///     source: String,
+/// }
+/// 
+/// let event = |timestamp, source: &str| Event { timestamp, source: source.to_string() };
+/// let merged = merge_sorted_by_keypath(
+///     vec![
+///         vec![event(1, "api"), event(5, "api")],
+///         vec![event(2, "db"), event(3, "db"), event(8, "db")],
+///         vec![event(4, "cache")],
+///     ],
+///     Event::timestamp(),
+///     |a, b| a.cmp(b),
+/// ).unwrap();
+/// let timestamps: Vec<u64> = merged.iter().map(|event| event.timestamp).collect();
+/// assert_eq!(timestamps, vec![1, 2, 3, 4, 5, 8]);
+/// ```
+pub fn merge_sorted_by_keypath<I, T, K, F>(
+    inputs: Vec<Vec<I>>,
+    keypath: K,
+    compare: F,
+) -> KeyPathResult<Vec<I>>
+where
+    I: KeyPathRoot<T>,
+    K: KeyPathAccess<T>,
+    F: for<'a> Fn(AccessOutput<'a, K, T>, AccessOutput<'a, K, T>) -> std::cmp::Ordering,
+{
+    /// Head of one input, ordered so the max-heap root is the smallest value
+    struct Head<'k, I, T, K, F> {
+        item: I,
+        input: usize,
+        keypath: &'k K,
+        compare: &'k F,
+        root: std::marker::PhantomData<fn(&T)>,
+    }
+    
+    impl<I: KeyPathRoot<T>, T, K: KeyPathAccess<T>, F: for<'a> Fn(AccessOutput<'a, K, T>, AccessOutput<'a, K, T>) -> std::cmp::Ordering> Head<'_, I, T, K, F> {
+        fn value(&self) -> AccessOutput<'_, K, T> {
+            self.keypath.access(self.item.keypath_root()).expect("keypath checked when pushed")
+        }
+    }
+    
+    impl<I: KeyPathRoot<T>, T, K: KeyPathAccess<T>, F: for<'a> Fn(AccessOutput<'a, K, T>, AccessOutput<'a, K, T>) -> std::cmp::Ordering> Ord for Head<'_, I, T, K, F> {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            (self.compare)(other.value(), self.value()).then(other.input.cmp(&self.input))
+        }
+    }
+    
+    impl<I: KeyPathRoot<T>, T, K: KeyPathAccess<T>, F: for<'a> Fn(AccessOutput<'a, K, T>, AccessOutput<'a, K, T>) -> std::cmp::Ordering> PartialOrd for Head<'_, I, T, K, F> {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    
+    impl<I: KeyPathRoot<T>, T, K: KeyPathAccess<T>, F: for<'a> Fn(AccessOutput<'a, K, T>, AccessOutput<'a, K, T>) -> std::cmp::Ordering> PartialEq for Head<'_, I, T, K, F> {
+        fn eq(&self, other: &Self) -> bool {
+            self.cmp(other).is_eq()
+        }
+    }
+    
+    impl<I: KeyPathRoot<T>, T, K: KeyPathAccess<T>, F: for<'a> Fn(AccessOutput<'a, K, T>, AccessOutput<'a, K, T>) -> std::cmp::Ordering> Eq for Head<'_, I, T, K, F> {}
+    
+    fn next_head<'k, I: KeyPathRoot<T>, T, K: KeyPathAccess<T>, F>(
+        items: &mut std::vec::IntoIter<I>,
+        input: usize,
+        keypath: &'k K,
+        compare: &'k F,
+    ) -> KeyPathResult<Option<Head<'k, I, T, K, F>>> {
+        match items.next() {
+            Some(item) => {
+                keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+                Ok(Some(Head { item, input, keypath, compare, root: std::marker::PhantomData }))
+            }
+            None => Ok(None),
+        }
+    }
+    
+    let total = inputs.iter().map(Vec::len).sum();
+    let mut inputs: Vec<std::vec::IntoIter<I>> = inputs.into_iter().map(Vec::into_iter).collect();
+    let mut heap = std::collections::BinaryHeap::with_capacity(inputs.len());
+    for (input, items) in inputs.iter_mut().enumerate() {
+        heap.extend(next_head(items, input, &keypath, &compare)?);
+    }
+    
+    let mut result = Vec::with_capacity(total);
+    while let Some(head) = heap.pop() {
+        let input = head.input;
+        result.push(head.item);
+        heap.extend(next_head(&mut inputs[input], input, &keypath, &compare)?);
+    }
+    Ok(result)
+}
+
 /// Combine collections using keypath values
 /// 
 /// # Examples
@@ -375,24 +1078,277 @@ where
 /// assert_eq!(combined[0], ("Alice".to_string(), "Charlie".to_string()));
 /// assert_eq!(combined[1], ("Bob".to_string(), "David".to_string()));
 /// ```
-pub fn zip_with_keypath<T1, T2, V1, V2, F, R>(
-    collection1: &[T1],
-    collection2: &[T2],
-    keypath1: KeyPaths<T1, V1>,
-    keypath2: KeyPaths<T2, V2>,
+pub fn zip_with_keypath<I1, I2, T1, T2, K1, K2, F, R>(
+    collection1: &[I1],
+    collection2: &[I2],
+    keypath1: K1,
+    keypath2: K2,
     f: F,
 ) -> KeyPathResult<Vec<R>>
 where
-    F: Fn(&V1, &V2) -> R,
+    I1: KeyPathRoot<T1>,
+    I2: KeyPathRoot<T2>,
+    K1: KeyPathAccess<T1>,
+    K2: KeyPathAccess<T2>,
+    F: for<'a> Fn(AccessOutput<'a, K1, T1>, AccessOutput<'a, K2, T2>) -> R,
 {
     let min_len = collection1.len().min(collection2.len());
     let mut result = Vec::new();
     
     for i in 0..min_len {
-        let val1 = keypath1.get(&collection1[i]).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
-        let val2 = keypath2.get(&collection2[i]).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        let val1 = keypath1.access(collection1[i].keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        let val2 = keypath2.access(collection2[i].keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
         result.push(f(val1, val2));
     }
     
     Ok(result)
-}
\ No newline at end of file
+}
+
+/// Mutate values in place through a writable keypath
+/// 
+/// The keypath must support mutable access (e.g. `field_w()` from `WritableKeypaths`);
+/// a readable-only keypath results in `KeyPathError::InvalidAccess`.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::{Keypath, WritableKeypaths};
+/// 
+/// #[derive(Keypath, WritableKeypaths, Debug, Clone)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+/// 
+/// let mut people = vec![
+///     Person { name: "Alice".to_string(), age: 30 },
+///     Person { name: "Bob".to_string(), age: 25 },
+/// ];
+/// 
+/// for_each_keypath_mut(&mut people, Person::age_w(), |age| *age += 1).unwrap();
+/// assert_eq!(people[0].age, 31);
+/// assert_eq!(people[1].age, 26);
+/// ```
+pub fn for_each_keypath_mut<T, V, F>(
+    collection: &mut [T],
+    keypath: KeyPaths<T, V>,
+    mut f: F,
+) -> KeyPathResult<()>
+where
+    F: FnMut(&mut V),
+{
+    for item in collection.iter_mut() {
+        let value = keypath.get_mut(item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath mutable access failed".to_string() })?;
+        f(value);
+    }
+    Ok(())
+}
+
+/// Mutate values at one keypath for elements whose value at another keypath matches
+/// 
+/// Returns the number of updated elements.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::{Keypath, WritableKeypaths};
+/// 
+/// #[derive(Keypath, WritableKeypaths, Debug, Clone)]
+/// struct Product {
+///     name: String,
+///     price: f64,
+///     category: String,
+/// }
+/// 
+/// let mut products = vec![
+///     Product { name: "Laptop".to_string(), price: 1000.0, category: "Electronics".to_string() },
+///     Product { name: "Book".to_string(), price: 20.0, category: "Books".to_string() },
+/// ];
+/// 
+/// // Apply a 10% discount to electronics only
+/// let updated = update_where(
+///     &mut products,
+///     Product::category(),
+///     |cat| cat == "Electronics",
+///     Product::price_w(),
+///     |price| *price *= 0.9,
+/// ).unwrap();
+/// 
+/// assert_eq!(updated, 1);
+/// assert_eq!(products[0].price, 900.0);
+/// assert_eq!(products[1].price, 20.0);
+/// ```
+pub fn update_where<T, K, V, P, U>(
+    collection: &mut [T],
+    condition_keypath: K,
+    predicate: P,
+    target_keypath: KeyPaths<T, V>,
+    mut updater: U,
+) -> KeyPathResult<usize>
+where
+    K: KeyPathAccess<T>,
+    P: for<'a> Fn(AccessOutput<'a, K, T>) -> bool,
+    U: FnMut(&mut V),
+{
+    let mut updated = 0;
+    for item in collection.iter_mut() {
+        let condition = condition_keypath.access(item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+        if predicate(condition) {
+            let value = target_keypath.get_mut(item).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath mutable access failed".to_string() })?;
+            updater(value);
+            updated += 1;
+        }
+    }
+    Ok(updated)
+}
+
+/// Transform every value reached by a traversal keypath, flattening the results
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_core::KeyPaths;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Product {
+///     name: String,
+///     price: f64,
+/// }
+/// 
+/// #[derive(Debug, Clone)]
+/// struct Order {
+///     products: Vec<Product>,
+/// }
+/// 
+/// let orders = vec![
+///     Order { products: vec![Product { name: "Laptop".to_string(), price: 999.0 }] },
+///     Order { products: vec![Product { name: "Mouse".to_string(), price: 25.0 }] },
+/// ];
+/// let names = flat_map_keypath(
+///     &orders,
+///     KeyPaths::readable(|order: &Order| &order.products).each().then(Product::name()),
+///     |name| name.to_uppercase(),
+/// ).unwrap();
+/// assert_eq!(names, vec!["LAPTOP", "MOUSE"]);
+/// ```
+pub fn flat_map_keypath<I, T, V, F, R>(
+    collection: &[I],
+    traversal: KeyPathTraversal<T, V>,
+    f: F,
+) -> KeyPathResult<Vec<R>>
+where
+    I: KeyPathRoot<T>,
+    T: 'static,
+    V: 'static,
+    F: Fn(&V) -> R,
+{
+    Ok(collection
+        .iter()
+        .flat_map(|item| traversal.iter(item.keypath_root()))
+        .map(f)
+        .collect())
+}
+
+/// Collect references to every value reached by a traversal keypath
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_core::KeyPaths;
+/// use std::collections::HashMap;
+/// 
+/// #[derive(Debug, Clone)]
+/// struct Team {
+///     scores: HashMap<String, u32>,
+/// }
+/// 
+/// let teams = vec![
+///     Team { scores: HashMap::from([("alice".to_string(), 3)]) },
+///     Team { scores: HashMap::from([("bob".to_string(), 5)]) },
+/// ];
+/// let scores = flatten_keypath(&teams, KeyPaths::readable(|team: &Team| &team.scores).each()).unwrap();
+/// assert_eq!(scores, vec![&3, &5]);
+/// ```
+pub fn flatten_keypath<I, T, V>(
+    collection: &[I],
+    traversal: KeyPathTraversal<T, V>,
+) -> KeyPathResult<Vec<&V>>
+where
+    I: KeyPathRoot<T>,
+    T: 'static,
+    V: 'static,
+{
+    Ok(collection
+        .iter()
+        .flat_map(|item| traversal.iter(item.keypath_root()))
+        .collect())
+}
+
+/// Keep elements where any value reached by a traversal keypath matches a predicate
+pub fn filter_by_flat_keypath<I, T, V, F>(
+    collection: Vec<I>,
+    traversal: KeyPathTraversal<T, V>,
+    predicate: F,
+) -> KeyPathResult<Vec<I>>
+where
+    I: KeyPathRoot<T>,
+    T: 'static,
+    V: 'static,
+    F: Fn(&V) -> bool,
+{
+    Ok(collection
+        .into_iter()
+        .filter(|item| traversal.iter(item.keypath_root()).any(&predicate))
+        .collect())
+}
+
+/// Fold over every value reached by a traversal keypath
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_core::KeyPaths;
+/// use key_paths_derive::Keypath;
+/// 
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Product {
+///     price: f64,
+/// }
+/// 
+/// #[derive(Debug, Clone)]
+/// struct Order {
+///     products: Vec<Product>,
+/// }
+/// 
+/// let orders = vec![
+///     Order { products: vec![Product { price: 10.0 }, Product { price: 5.0 }] },
+///     Order { products: vec![] },
+///     Order { products: vec![Product { price: 2.5 }] },
+/// ];
+/// let prices = KeyPaths::readable(|order: &Order| &order.products).each().then(Product::price());
+/// let total = fold_flat_keypath(&orders, prices, 0.0, |acc, &price| acc + price).unwrap();
+/// assert_eq!(total, 17.5);
+/// ```
+pub fn fold_flat_keypath<I, T, V, F, B>(
+    collection: &[I],
+    traversal: KeyPathTraversal<T, V>,
+    init: B,
+    f: F,
+) -> KeyPathResult<B>
+where
+    I: KeyPathRoot<T>,
+    T: 'static,
+    V: 'static,
+    F: Fn(B, &V) -> B,
+{
+    Ok(collection
+        .iter()
+        .flat_map(|item| traversal.iter(item.keypath_root()))
+        .fold(init, f))
+}
@@ -34,12 +34,12 @@
 //!     Rc::new(Person { name: "Bob".to_string(), age: 25 }),
 //! ];
 //!
-//! // Filter people by age and extract their names (lazy evaluation)
+//! // Filter people by age and extract their names; keypaths look through the `Rc`
 //! let young_people_names: Vec<String> = people
 //!     .iter()
 //!     .filter_by_keypath(Person::age(), |&age| age < 30)
-//!     .map_keypath(Person::name(), |name| name.clone())
-//!     .collect();
+//!     .into_iter()
+//!     .map_keypath(Person::name(), |name| name.clone());
 //!
 //! println!("Young people: {:?}", young_people_names);
 //! ```
@@ -65,13 +65,15 @@
 //!     Rc::new(Product { name: "Book".to_string(), price: 19.99, category: "Books".to_string() }),
 //! ];
 //!
-//! // Lazy evaluation - no intermediate collections created
+//! // Iterate by reference - the products themselves are never cloned
 //! let expensive_electronics: Vec<String> = products
 //!     .iter()
 //!     .filter_by_keypath(Product::category(), |cat| cat == "Electronics")
+//!     .into_iter()
 //!     .filter_by_keypath(Product::price(), |&price| price > 100.0)
-//!     .map_keypath(Product::name(), |name| name.clone())
-//!     .collect();
+//!     .into_iter()
+//!     .map_keypath(Product::name(), |name| name.clone());
+//! assert_eq!(expensive_electronics, vec!["Laptop"]);
 //! ```
 //!
 //! ### Memory Efficient Operations
@@ -96,25 +98,43 @@
 //! // Memory efficient - uses Rc to avoid cloning
 //! let user_emails: Vec<String> = users
 //!     .iter()
-//!     .map_keypath(User::email(), |email| email.clone())
-//!     .collect();
+//!     .map_keypath(User::email(), |email| email.clone());
 //! ```
 
 pub mod error;
+pub mod access;
 pub mod higher_order;
 pub mod traits;
 pub mod composable;
 pub mod collections;
+pub mod predicate;
+pub mod query;
+pub mod dynamic;
+pub mod schema;
+pub mod traversal;
+pub mod optics;
 pub mod parallel;
 pub mod async_ops;
 
+/// Items used by code generated from `rust-prelude-plus-derive`
+#[doc(hidden)]
+pub mod __private {
+    pub use key_paths_core::KeyPaths;
+}
+
 /// Re-exports for convenient usage
 pub mod prelude {
     pub use crate::error::*;
+    pub use crate::access::{cache_keypath, computed_keypath, zip_keypaths, AccessOutput, CachedKeyPath, ComputedKeyPath, KeyPathAccess, KeyPathsZipExt, OwnedKeyPathAccess, ZippedKeyPath};
     pub use crate::higher_order::*;
     pub use crate::traits::*;
     pub use crate::composable::{pipe, chain_keypath_ops, when_keypath, unless_keypath, KeyPathsChain, ComposableIterator};
-    pub use crate::collections::{KeyPathsCollectionExt, specialized};
+    pub use crate::collections::{Binning, HistogramBucket, HistogramValue, KeepOccurrence, KeyPathReport, KeyPathsCollectionExt, KeyPathsSortedExt, PivotTable, KeyPathsCollectionMutExt, specialized};
+    pub use crate::predicate::{identity_keypath, KeyPathPredicate, PredicateBuilder};
+    pub use crate::dynamic::{collect_any_keypath, select_by_names, AnyKeyPath, AnyValue, KeyPathRegistry};
+    pub use crate::schema::{FieldSchema, KeyPathSchema};
+    pub use crate::traversal::{KeyPathTraversal, KeyPathsTraversalExt, Traversable};
+    pub use crate::optics::{extract_variant, filter_variant, map_variant, Iso, IsoKeyPath, KeyPathsIsoExt};
     
         #[cfg(feature = "parallel")]
         pub use crate::parallel::*;
@@ -0,0 +1,293 @@
+//! Prisms over enum variants and isomorphisms between value representations
+//!
+//! A keypath built with `KeyPaths::readable_enum` or `KeyPaths::writable_enum` focuses on the
+//! payload of one enum variant and is absent for every other variant, which makes it a prism.
+//! `filter_variant`, `extract_variant` and `map_variant` use such keypaths to work with the
+//! elements of a collection that match a variant.
+//!
+//! An `Iso` converts a value to another representation and back without losing information,
+//! e.g. cents to dollars. Attaching one to a keypath with `iso()` gives an `IsoKeyPath` that
+//! reads and writes through the conversion.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use rust_prelude_plus::prelude::*;
+//! use key_paths_core::KeyPaths;
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Event {
+//!     Click { x: i32, y: i32 },
+//!     Scroll(f64),
+//! }
+//!
+//! let scroll = KeyPaths::readable_enum(Event::Scroll, |event: &Event| match event {
+//!     Event::Scroll(delta) => Some(delta),
+//!     _ => None,
+//! });
+//!
+//! let events = vec![Event::Scroll(1.5), Event::Click { x: 1, y: 2 }, Event::Scroll(-0.5)];
+//! assert_eq!(extract_variant(&events, scroll.clone()), vec![&1.5, &-0.5]);
+//!
+//! let doubled = map_variant(events, scroll, |delta| delta * 2.0).unwrap();
+//! assert_eq!(doubled[0], Event::Scroll(3.0));
+//! assert_eq!(doubled[1], Event::Click { x: 1, y: 2 });
+//! ```
+
+use key_paths_core::KeyPaths;
+use crate::error::{KeyPathError, KeyPathResult};
+use crate::traits::KeyPathRoot;
+use crate::traversal::project;
+use std::rc::Rc;
+
+/// Keep the elements whose variant is focused by a prism keypath
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_core::KeyPaths;
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Shape {
+///     Circle(f64),
+///     Square(f64),
+/// }
+///
+/// let circle = KeyPaths::readable_enum(Shape::Circle, |shape: &Shape| match shape {
+///     Shape::Circle(radius) => Some(radius),
+///     _ => None,
+/// });
+///
+/// let circles = filter_variant(vec![Shape::Circle(1.0), Shape::Square(2.0)], circle);
+/// assert_eq!(circles, vec![Shape::Circle(1.0)]);
+/// ```
+pub fn filter_variant<I, E, P>(collection: Vec<I>, keypath: KeyPaths<E, P>) -> Vec<I>
+where
+    I: KeyPathRoot<E>,
+{
+    collection
+        .into_iter()
+        .filter(|item| keypath.get(item.keypath_root()).is_some())
+        .collect()
+}
+
+/// Payloads of the elements whose variant is focused by a prism keypath, in order
+pub fn extract_variant<'a, I, E: 'a, P>(collection: &'a [I], keypath: KeyPaths<E, P>) -> Vec<&'a P>
+where
+    I: KeyPathRoot<E>,
+{
+    collection
+        .iter()
+        .filter_map(|item| project(&keypath, item.keypath_root()))
+        .collect()
+}
+
+/// Transform the payload of every element with the focused variant, leaving other elements unchanged
+///
+/// Writable keypaths update the payload in place; `readable_enum` keypaths rebuild the variant
+/// with their `embed` function. Other keypaths cannot rebuild an element and return
+/// `KeyPathError::InvalidAccess`.
+pub fn map_variant<E, P, F>(collection: Vec<E>, keypath: KeyPaths<E, P>, f: F) -> KeyPathResult<Vec<E>>
+where
+    F: Fn(&P) -> P,
+{
+    match &keypath {
+        KeyPaths::ReadableEnum { extract, embed } => Ok(collection
+            .into_iter()
+            .map(|item| match extract(&item).map(&f) {
+                Some(payload) => embed(payload),
+                None => item,
+            })
+            .collect()),
+        KeyPaths::WritableEnum { .. } | KeyPaths::FailableWritable(_) | KeyPaths::FailableCombined { .. } => {
+            let mut collection = collection;
+            for item in collection.iter_mut() {
+                if let Some(payload) = keypath.get_mut(item) {
+                    *payload = f(payload);
+                }
+            }
+            Ok(collection)
+        }
+        _ => Err(KeyPathError::InvalidAccess {
+            message: "KeyPath cannot update an enum variant".to_string(),
+        }),
+    }
+}
+
+/// A lossless conversion between two representations of a value
+///
+/// `get` converts from `A` to `B` and `reverse_get` converts back; applying one after the other
+/// should return the original value.
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+///
+/// let dollars = Iso::new(|&cents: &u64| cents as f64 / 100.0, |&dollars: &f64| (dollars * 100.0).round() as u64);
+/// assert_eq!(dollars.get(&1999), 19.99);
+/// assert_eq!(dollars.reverse_get(&5.0), 500);
+/// assert_eq!(dollars.reverse().get(&0.25), 25);
+/// ```
+pub struct Iso<A, B> {
+    forward: Rc<dyn Fn(&A) -> B>,
+    backward: Rc<dyn Fn(&B) -> A>,
+}
+
+impl<A, B> Clone for Iso<A, B> {
+    fn clone(&self) -> Self {
+        Self {
+            forward: self.forward.clone(),
+            backward: self.backward.clone(),
+        }
+    }
+}
+
+impl<A, B> std::fmt::Debug for Iso<A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Iso")
+            .field("from", &std::any::type_name::<A>())
+            .field("to", &std::any::type_name::<B>())
+            .finish()
+    }
+}
+
+impl<A: 'static, B: 'static> Iso<A, B> {
+    /// Create an iso from a conversion and its inverse
+    pub fn new(forward: impl Fn(&A) -> B + 'static, backward: impl Fn(&B) -> A + 'static) -> Self {
+        Self {
+            forward: Rc::new(forward),
+            backward: Rc::new(backward),
+        }
+    }
+
+    /// Convert from `A` to `B`
+    pub fn get(&self, value: &A) -> B {
+        (self.forward)(value)
+    }
+
+    /// Convert from `B` back to `A`
+    pub fn reverse_get(&self, value: &B) -> A {
+        (self.backward)(value)
+    }
+
+    /// The same iso in the other direction
+    pub fn reverse(self) -> Iso<B, A> {
+        Iso {
+            forward: self.backward,
+            backward: self.forward,
+        }
+    }
+
+    /// Compose with a further conversion from `B` to `C`
+    pub fn then<C: 'static>(self, next: Iso<B, C>) -> Iso<A, C> {
+        let (forward, backward) = (self.forward, self.backward);
+        let (next_forward, next_backward) = (next.forward, next.backward);
+        Iso::new(
+            move |value: &A| next_forward(&forward(value)),
+            move |value: &C| backward(&next_backward(value)),
+        )
+    }
+}
+
+/// A keypath whose value is seen through an `Iso`
+///
+/// Reading needs a readable keypath and writing a writable one (e.g. `field_w()` from
+/// `WritableKeypaths`), so a field usually gets one `IsoKeyPath` for each.
+pub struct IsoKeyPath<T, A, B> {
+    keypath: KeyPaths<T, A>,
+    iso: Iso<A, B>,
+}
+
+impl<T, A, B> Clone for IsoKeyPath<T, A, B> {
+    fn clone(&self) -> Self {
+        Self {
+            keypath: self.keypath.clone(),
+            iso: self.iso.clone(),
+        }
+    }
+}
+
+impl<T, A, B> std::fmt::Debug for IsoKeyPath<T, A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IsoKeyPath")
+            .field("root", &std::any::type_name::<T>())
+            .field("iso", &self.iso)
+            .finish()
+    }
+}
+
+impl<T, A: 'static, B: 'static> IsoKeyPath<T, A, B> {
+    /// Attach an iso to a keypath
+    pub fn new(keypath: KeyPaths<T, A>, iso: Iso<A, B>) -> Self {
+        Self { keypath, iso }
+    }
+
+    /// The underlying keypath
+    pub fn keypath(&self) -> &KeyPaths<T, A> {
+        &self.keypath
+    }
+
+    /// The conversion applied to the keypath's value
+    pub fn iso(&self) -> &Iso<A, B> {
+        &self.iso
+    }
+
+    /// Read the converted value
+    pub fn get<I: KeyPathRoot<T>>(&self, root: &I) -> KeyPathResult<B> {
+        self.keypath
+            .get(root.keypath_root())
+            .map(|value| self.iso.get(value))
+            .ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })
+    }
+
+    /// Read the converted value of every element
+    pub fn get_all<I: KeyPathRoot<T>>(&self, collection: &[I]) -> KeyPathResult<Vec<B>> {
+        collection.iter().map(|item| self.get(item)).collect()
+    }
+
+    /// Write a value given in the converted representation
+    pub fn set(&self, root: &mut T, value: B) -> KeyPathResult<()> {
+        let slot = self.keypath.get_mut(root).ok_or_else(|| KeyPathError::InvalidAccess {
+            message: "KeyPath mutable access failed".to_string(),
+        })?;
+        *slot = self.iso.reverse_get(&value);
+        Ok(())
+    }
+
+    /// Update the value in the converted representation
+    pub fn modify<F>(&self, root: &mut T, f: F) -> KeyPathResult<()>
+    where
+        F: FnOnce(B) -> B,
+    {
+        let slot = self.keypath.get_mut(root).ok_or_else(|| KeyPathError::InvalidAccess {
+            message: "KeyPath mutable access failed".to_string(),
+        })?;
+        *slot = self.iso.reverse_get(&f(self.iso.get(slot)));
+        Ok(())
+    }
+
+    /// Update the value of every element in the converted representation
+    pub fn modify_all<F>(&self, collection: &mut [T], f: F) -> KeyPathResult<()>
+    where
+        F: Fn(B) -> B,
+    {
+        for item in collection.iter_mut() {
+            self.modify(item, &f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Attach an `Iso` to a keypath
+pub trait KeyPathsIsoExt<T, A> {
+    /// View the keypath's value through `iso`
+    fn iso<B: 'static>(self, iso: Iso<A, B>) -> IsoKeyPath<T, A, B>;
+}
+
+impl<T, A: 'static> KeyPathsIsoExt<T, A> for KeyPaths<T, A> {
+    fn iso<B: 'static>(self, iso: Iso<A, B>) -> IsoKeyPath<T, A, B> {
+        IsoKeyPath::new(self, iso)
+    }
+}
@@ -4,8 +4,9 @@
 use {
     rayon::prelude::*,
     key_paths_core::KeyPaths,
+    crate::access::{AccessOutput, KeyPathAccess, OwnedKeyPathAccess},
     crate::error::{KeyPathResult, KeyPathError},
-    crate::traits::KeyPathsOperable,
+    crate::collections::utils::BoundedHeap,
 };
 
 #[cfg(feature = "parallel")]
@@ -14,22 +15,21 @@ pub mod parallel_collections {
     use super::*;
     
     /// Parallel map over collection with keypath
-    pub fn par_map_keypath<T, V, F, R>(
+    pub fn par_map_keypath<T, K, F, R>(
         collection: Vec<T>,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         f: F,
     ) -> KeyPathResult<Vec<R>>
     where
-        T: Send + Sync + KeyPathsOperable,
-        V: Send + Sync,
-        KeyPaths<T, V>: Send + Sync,
-        F: Fn(&V) -> R + Send + Sync,
+        T: Send + Sync,
+        K: KeyPathAccess<T> + Sync,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> R + Send + Sync,
         R: Send,
     {
         let result: Vec<R> = collection
             .into_par_iter()
             .map(|item| {
-                let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let value = keypath.access(&item).unwrap_or_else(|| {
                     panic!("KeyPath access failed in par_map_keypath")
                 });
                 f(value)
@@ -39,21 +39,20 @@ pub mod parallel_collections {
     }
     
     /// Parallel filter by keypath predicate
-    pub fn par_filter_by_keypath<T, V, F>(
+    pub fn par_filter_by_keypath<T, K, F>(
         collection: Vec<T>,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         predicate: F,
     ) -> KeyPathResult<Vec<T>>
     where
-        T: Send + Sync + KeyPathsOperable,
-        V: Send + Sync,
-        KeyPaths<T, V>: Send + Sync,
-        F: Fn(&V) -> bool + Send + Sync,
+        T: Send + Sync,
+        K: KeyPathAccess<T> + Sync,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool + Send + Sync,
     {
         let result: Vec<T> = collection
             .into_par_iter()
             .filter(|item| {
-                let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let value = keypath.access(item).unwrap_or_else(|| {
                     panic!("KeyPath access failed in par_filter_by_keypath")
                 });
                 predicate(value)
@@ -63,21 +62,20 @@ pub mod parallel_collections {
     }
     
     /// Parallel find by keypath predicate
-    pub fn par_find_by_keypath<T, V, F>(
+    pub fn par_find_by_keypath<T, K, F>(
         collection: Vec<T>,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         predicate: F,
     ) -> KeyPathResult<Option<T>>
     where
-        T: Send + Sync + KeyPathsOperable,
-        V: Send + Sync,
-        KeyPaths<T, V>: Send + Sync,
-        F: Fn(&V) -> bool + Send + Sync,
+        T: Send + Sync,
+        K: KeyPathAccess<T> + Sync,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool + Send + Sync,
     {
         let result = collection
             .into_par_iter()
             .find_any(|item| {
-                let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let value = keypath.access(item).unwrap_or_else(|| {
                     panic!("KeyPath access failed in par_find_by_keypath")
                 });
                 predicate(value)
@@ -86,43 +84,41 @@ pub mod parallel_collections {
     }
     
     /// Parallel collect keypath values
-    pub fn par_collect_keypath<T, V>(
+    pub fn par_collect_keypath<T, K>(
         collection: Vec<T>,
-        keypath: KeyPaths<T, V>,
-    ) -> KeyPathResult<Vec<V>>
+        keypath: K,
+    ) -> KeyPathResult<Vec<K::Value>>
     where
-        T: Send + Sync + KeyPathsOperable,
-        V: Send + Sync + Clone,
-        KeyPaths<T, V>: Send + Sync,
+        T: Send + Sync,
+        K: OwnedKeyPathAccess<T> + Sync,
+        K::Value: Send,
     {
-        let result: Vec<V> = collection
+        let result: Vec<K::Value> = collection
             .into_par_iter()
             .map(|item| {
-                let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                keypath.access_owned(&item).unwrap_or_else(|| {
                     panic!("KeyPath access failed in par_collect_keypath")
-                });
-                value.clone()
+                })
             })
             .collect();
         Ok(result)
     }
     
     /// Parallel count by keypath predicate
-    pub fn par_count_by_keypath<T, V, F>(
+    pub fn par_count_by_keypath<T, K, F>(
         collection: Vec<T>,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         predicate: F,
     ) -> KeyPathResult<usize>
     where
-        T: Send + Sync + KeyPathsOperable,
-        V: Send + Sync,
-        KeyPaths<T, V>: Send + Sync,
-        F: Fn(&V) -> bool + Send + Sync,
+        T: Send + Sync,
+        K: KeyPathAccess<T> + Sync,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool + Send + Sync,
     {
         let count = collection
             .into_par_iter()
             .filter(|item| {
-                let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let value = keypath.access(item).unwrap_or_else(|| {
                     panic!("KeyPath access failed in par_count_by_keypath")
                 });
                 predicate(value)
@@ -132,21 +128,20 @@ pub mod parallel_collections {
     }
     
     /// Parallel any by keypath predicate
-    pub fn par_any_by_keypath<T, V, F>(
+    pub fn par_any_by_keypath<T, K, F>(
         collection: Vec<T>,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         predicate: F,
     ) -> KeyPathResult<bool>
     where
-        T: Send + Sync + KeyPathsOperable,
-        V: Send + Sync,
-        KeyPaths<T, V>: Send + Sync,
-        F: Fn(&V) -> bool + Send + Sync,
+        T: Send + Sync,
+        K: KeyPathAccess<T> + Sync,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool + Send + Sync,
     {
         let result = collection
             .into_par_iter()
             .any(|item| {
-                let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let value = keypath.access(&item).unwrap_or_else(|| {
                     panic!("KeyPath access failed in par_any_by_keypath")
                 });
                 predicate(value)
@@ -155,27 +150,148 @@ pub mod parallel_collections {
     }
     
     /// Parallel all by keypath predicate
-    pub fn par_all_by_keypath<T, V, F>(
+    pub fn par_all_by_keypath<T, K, F>(
         collection: Vec<T>,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         predicate: F,
     ) -> KeyPathResult<bool>
     where
-        T: Send + Sync + KeyPathsOperable,
-        V: Send + Sync,
-        KeyPaths<T, V>: Send + Sync,
-        F: Fn(&V) -> bool + Send + Sync,
+        T: Send + Sync,
+        K: KeyPathAccess<T> + Sync,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool + Send + Sync,
     {
         let result = collection
             .into_par_iter()
             .all(|item| {
-                let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+                let value = keypath.access(&item).unwrap_or_else(|| {
                     panic!("KeyPath access failed in par_all_by_keypath")
                 });
                 predicate(value)
             });
         Ok(result)
     }
+
+    /// Parallel in-place mutation through a writable keypath
+    ///
+    /// The keypath is resolved on the calling thread, so only the values
+    /// themselves need to be `Send`.
+    pub fn par_for_each_keypath_mut<T, V, F>(
+        collection: &mut [T],
+        keypath: KeyPaths<T, V>,
+        f: F,
+    ) -> KeyPathResult<()>
+    where
+        V: Send,
+        F: Fn(&mut V) + Send + Sync,
+    {
+        let values = collection
+            .iter_mut()
+            .map(|item| {
+                keypath.get_mut(item).ok_or_else(|| KeyPathError::InvalidAccess {
+                    message: "KeyPath mutable access failed in par_for_each_keypath_mut".to_string(),
+                })
+            })
+            .collect::<KeyPathResult<Vec<&mut V>>>()?;
+        values.into_par_iter().for_each(f);
+        Ok(())
+    }
+
+    /// Parallel top-k: the `k` elements with the largest keypath values, largest first
+    ///
+    /// Each thread keeps a bounded heap of its best `k` elements and the heaps are merged,
+    /// so the work is O(n log k). Ties keep the earlier element. Each value is read once and the
+    /// comparison receives references to the values, as in `KeyPathsCollectionExt::top_k_by_keypath`.
+    pub fn par_top_k_by_keypath<T, K, F>(
+        collection: Vec<T>,
+        keypath: K,
+        k: usize,
+        compare: F,
+    ) -> KeyPathResult<Vec<T>>
+    where
+        K: OwnedKeyPathAccess<T>,
+        K::Value: Send,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering + Sync,
+    {
+        par_select_k(
+            collection,
+            keypath,
+            k,
+            move |a: &K::Value, b: &K::Value| compare(b, a),
+            "par_top_k_by_keypath",
+        )
+    }
+    
+    /// Parallel bottom-k: the `k` elements with the smallest keypath values, smallest first
+    pub fn par_bottom_k_by_keypath<T, K, F>(
+        collection: Vec<T>,
+        keypath: K,
+        k: usize,
+        compare: F,
+    ) -> KeyPathResult<Vec<T>>
+    where
+        K: OwnedKeyPathAccess<T>,
+        K::Value: Send,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering + Sync,
+    {
+        par_select_k(
+            collection,
+            keypath,
+            k,
+            move |a: &K::Value, b: &K::Value| compare(a, b),
+            "par_bottom_k_by_keypath",
+        )
+    }
+    
+    /// Select the `k` best-ranked elements with per-thread bounded heaps
+    ///
+    /// The keypath is resolved on the calling thread; only the values are shared with rayon.
+    fn par_select_k<T, K, F>(
+        collection: Vec<T>,
+        keypath: K,
+        k: usize,
+        rank: F,
+        operation: &str,
+    ) -> KeyPathResult<Vec<T>>
+    where
+        K: OwnedKeyPathAccess<T>,
+        K::Value: Send,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering + Sync,
+    {
+        let values = collection
+            .iter()
+            .map(|item| {
+                keypath.access_owned(item).ok_or_else(|| KeyPathError::InvalidAccess {
+                    message: format!("KeyPath access failed in {}", operation),
+                })
+            })
+            .collect::<KeyPathResult<Vec<_>>>()?;
+        
+        let selected = values
+            .into_par_iter()
+            .enumerate()
+            .fold(
+                || BoundedHeap::new(k, &rank),
+                |mut heap, (index, value)| {
+                    heap.push(value, index, ());
+                    heap
+                },
+            )
+            .reduce(
+                || BoundedHeap::new(k, &rank),
+                |mut left, right| {
+                    left.merge(right);
+                    left
+                },
+            )
+            .into_sorted_vec();
+        let order: Vec<usize> = selected.into_iter().map(|(_, index, _)| index).collect();
+        
+        let mut slots: Vec<Option<T>> = collection.into_iter().map(Some).collect();
+        Ok(order
+            .into_iter()
+            .filter_map(|index| slots[index].take())
+            .collect())
+    }
 }
 
 #[cfg(feature = "parallel")]
@@ -195,17 +311,16 @@ pub mod parallel_pools {
     }
     
     /// Execute keypath operations on custom thread pool
-    pub fn execute_on_pool<T, V, F, R>(
+    pub fn execute_on_pool<T, K, F, R>(
         pool: &ThreadPool,
         collection: Vec<T>,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         operation: F,
     ) -> KeyPathResult<Vec<R>>
     where
-        T: Send + Sync + KeyPathsOperable,
-        V: Send + Sync,
-        KeyPaths<T, V>: Send + Sync,
-        F: Fn(&V) -> R + Send + Sync,
+        T: Send + Sync,
+        K: KeyPathAccess<T> + Send + Sync,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> R + Send + Sync,
         R: Send,
     {
         pool.install(|| {
@@ -214,17 +329,16 @@ pub mod parallel_pools {
     }
     
     /// Execute keypath filter on custom thread pool
-    pub fn filter_on_pool<T, V, F>(
+    pub fn filter_on_pool<T, K, F>(
         pool: &ThreadPool,
         collection: Vec<T>,
-        keypath: KeyPaths<T, V>,
+        keypath: K,
         predicate: F,
     ) -> KeyPathResult<Vec<T>>
     where
-        T: Send + Sync + KeyPathsOperable,
-        V: Send + Sync,
-        KeyPaths<T, V>: Send + Sync,
-        F: Fn(&V) -> bool + Send + Sync,
+        T: Send + Sync,
+        K: KeyPathAccess<T> + Send + Sync,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool + Send + Sync,
     {
         pool.install(|| {
             parallel_collections::par_filter_by_keypath(collection, keypath, predicate)
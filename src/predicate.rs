@@ -0,0 +1,400 @@
+//! Named, reusable keypath predicates
+//!
+//! This module provides `KeyPathPredicate`, a cloneable predicate over a root type that can be
+//! combined with boolean operators and describes itself in its `Debug` output. Predicates are
+//! usually built from a keypath with the helpers on `PredicateBuilder`.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use rust_prelude_plus::prelude::*;
+//! use key_paths_derive::Keypath;
+//!
+//! #[derive(Keypath, Debug, Clone)]
+//! struct Person {
+//!     name: String,
+//!     age: u32,
+//! }
+//!
+//! let adult = KeyPathPredicate::field("age", Person::age()).ge(18);
+//! let starts_with_a = KeyPathPredicate::field("name", Person::name()).starts_with("A");
+//! let predicate = adult.and(starts_with_a);
+//!
+//! assert_eq!(format!("{:?}", predicate), "(age >= 18 && name starts_with \"A\")");
+//!
+//! let people = vec![
+//!     Person { name: "Alice".to_string(), age: 30 },
+//!     Person { name: "Adam".to_string(), age: 12 },
+//!     Person { name: "Bob".to_string(), age: 25 },
+//! ];
+//! let matching: Vec<&Person> = people.iter().filter(|p| predicate.test(p)).collect();
+//! assert_eq!(matching.len(), 1);
+//! assert_eq!(matching[0].name, "Alice");
+//! ```
+
+use key_paths_core::KeyPaths;
+use std::collections::HashSet;
+use std::fmt;
+use std::rc::Rc;
+
+/// A reusable, composable predicate over `T`
+///
+/// A predicate on a keypath value type can be passed to `filter_by_keypath` and friends
+/// through [`KeyPathPredicate::as_fn`]:
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let people = vec![
+///     Person { name: "Alice".to_string(), age: 30 },
+///     Person { name: "Bob".to_string(), age: 25 },
+/// ];
+///
+/// let thirties = KeyPathPredicate::new("between 30 and 39", |&age: &u32| (30..40).contains(&age));
+/// let result = filter_by_keypath(people, Person::age(), thirties.as_fn()).unwrap();
+/// assert_eq!(result.len(), 1);
+/// ```
+pub struct KeyPathPredicate<T> {
+    test: Rc<dyn Fn(&T) -> bool>,
+    description: String,
+}
+
+impl<T> KeyPathPredicate<T> {
+    /// Create a predicate from a description and a test function
+    pub fn new<F>(description: impl Into<String>, test: F) -> Self
+    where
+        F: Fn(&T) -> bool + 'static,
+    {
+        Self {
+            test: Rc::new(test),
+            description: description.into(),
+        }
+    }
+
+    /// A predicate that matches every element
+    pub fn always() -> Self {
+        Self::new("true", |_| true)
+    }
+
+    /// A predicate that matches no element
+    pub fn never() -> Self {
+        Self::new("false", |_| false)
+    }
+
+    /// Start building a predicate over the value at a keypath
+    ///
+    /// The value is referred to as `value` in the predicate description; use
+    /// [`KeyPathPredicate::field`] to give it a name.
+    pub fn on<V>(keypath: KeyPaths<T, V>) -> PredicateBuilder<T, V> {
+        PredicateBuilder {
+            name: "value".to_string(),
+            keypath,
+        }
+    }
+
+    /// Start building a predicate over a named field
+    pub fn field<V>(name: impl Into<String>, keypath: KeyPaths<T, V>) -> PredicateBuilder<T, V> {
+        PredicateBuilder {
+            name: name.into(),
+            keypath,
+        }
+    }
+
+    /// Evaluate the predicate against an element
+    pub fn test(&self, item: &T) -> bool {
+        (self.test)(item)
+    }
+
+    /// Human readable description of the predicate
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Borrow the predicate as a closure, for APIs that take `Fn(&T) -> bool`
+    pub fn as_fn(&self) -> impl Fn(&T) -> bool + '_ {
+        move |item| self.test(item)
+    }
+
+    /// Convert the predicate into an owned closure
+    pub fn into_fn(self) -> impl Fn(&T) -> bool {
+        move |item| (self.test)(item)
+    }
+
+    /// Match elements satisfying both predicates
+    pub fn and(self, other: Self) -> Self
+    where
+        T: 'static,
+    {
+        let description = format!("({} && {})", self.description, other.description);
+        Self::new(description, move |item| self.test(item) && other.test(item))
+    }
+
+    /// Match elements satisfying either predicate
+    pub fn or(self, other: Self) -> Self
+    where
+        T: 'static,
+    {
+        let description = format!("({} || {})", self.description, other.description);
+        Self::new(description, move |item| self.test(item) || other.test(item))
+    }
+
+    /// Match elements satisfying exactly one of the predicates
+    pub fn xor(self, other: Self) -> Self
+    where
+        T: 'static,
+    {
+        let description = format!("({} ^ {})", self.description, other.description);
+        Self::new(description, move |item| self.test(item) != other.test(item))
+    }
+
+    /// Match elements that do not satisfy the predicate
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self
+    where
+        T: 'static,
+    {
+        let description = format!("!{}", self.description);
+        Self::new(description, move |item| !self.test(item))
+    }
+
+    /// Lift the predicate to a parent type through a keypath
+    ///
+    /// Elements whose keypath access fails do not match.
+    pub fn at<R>(self, keypath: KeyPaths<R, T>) -> KeyPathPredicate<R>
+    where
+        T: 'static,
+        R: 'static,
+    {
+        let description = self.description.clone();
+        KeyPathPredicate::new(description, move |item: &R| {
+            keypath.get(item).is_some_and(|value| self.test(value))
+        })
+    }
+}
+
+impl<T> Clone for KeyPathPredicate<T> {
+    fn clone(&self) -> Self {
+        Self {
+            test: Rc::clone(&self.test),
+            description: self.description.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for KeyPathPredicate<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+impl<T> fmt::Display for KeyPathPredicate<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+impl<T: 'static> std::ops::BitAnd for KeyPathPredicate<T> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.and(rhs)
+    }
+}
+
+impl<T: 'static> std::ops::BitOr for KeyPathPredicate<T> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.or(rhs)
+    }
+}
+
+impl<T: 'static> std::ops::BitXor for KeyPathPredicate<T> {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        self.xor(rhs)
+    }
+}
+
+impl<T: 'static> std::ops::Not for KeyPathPredicate<T> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        KeyPathPredicate::not(self)
+    }
+}
+
+/// A keypath from an element to itself
+///
+/// Lets predicates over whole elements, such as a `KeyPathPredicate<T>`, be used with
+/// operations that take a keypath and a value predicate.
+///
+/// ```rust
+/// use rust_prelude_plus::prelude::*;
+/// use key_paths_derive::Keypath;
+///
+/// #[derive(Keypath, Debug, Clone)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let people = vec![
+///     Person { name: "Alice".to_string(), age: 30 },
+///     Person { name: "Bob".to_string(), age: 25 },
+/// ];
+///
+/// let predicate = KeyPathPredicate::field("age", Person::age()).gt(26);
+/// let (older, younger) = partition_by_keypath(people, identity_keypath(), predicate.as_fn()).unwrap();
+/// assert_eq!(older[0].name, "Alice");
+/// assert_eq!(younger[0].name, "Bob");
+/// ```
+pub fn identity_keypath<T>() -> KeyPaths<T, T> {
+    KeyPaths::readable(|item: &T| item)
+}
+
+/// Builder for predicates over the value at a keypath
+///
+/// Elements whose keypath access fails never match.
+pub struct PredicateBuilder<T, V> {
+    name: String,
+    keypath: KeyPaths<T, V>,
+}
+
+impl<T: 'static, V: 'static> PredicateBuilder<T, V> {
+    /// Match values satisfying a custom test
+    pub fn matches<F>(self, description: impl Into<String>, test: F) -> KeyPathPredicate<T>
+    where
+        F: Fn(&V) -> bool + 'static,
+    {
+        let keypath = self.keypath;
+        let description = format!("{} {}", self.name, description.into());
+        KeyPathPredicate::new(description, move |item: &T| {
+            keypath.get(item).is_some_and(&test)
+        })
+    }
+
+    fn compare<F>(self, op: &str, operand: V, test: F) -> KeyPathPredicate<T>
+    where
+        V: fmt::Debug,
+        F: Fn(&V, &V) -> bool + 'static,
+    {
+        let description = format!("{} {:?}", op, operand);
+        self.matches(description, move |value| test(value, &operand))
+    }
+
+    /// Match values equal to `expected`
+    pub fn eq(self, expected: V) -> KeyPathPredicate<T>
+    where
+        V: PartialEq + fmt::Debug,
+    {
+        self.compare("==", expected, |value, operand| value == operand)
+    }
+
+    /// Match values not equal to `expected`
+    pub fn ne(self, expected: V) -> KeyPathPredicate<T>
+    where
+        V: PartialEq + fmt::Debug,
+    {
+        self.compare("!=", expected, |value, operand| value != operand)
+    }
+
+    /// Match values greater than `bound`
+    pub fn gt(self, bound: V) -> KeyPathPredicate<T>
+    where
+        V: PartialOrd + fmt::Debug,
+    {
+        self.compare(">", bound, |value, operand| value > operand)
+    }
+
+    /// Match values greater than or equal to `bound`
+    pub fn ge(self, bound: V) -> KeyPathPredicate<T>
+    where
+        V: PartialOrd + fmt::Debug,
+    {
+        self.compare(">=", bound, |value, operand| value >= operand)
+    }
+
+    /// Match values less than `bound`
+    pub fn lt(self, bound: V) -> KeyPathPredicate<T>
+    where
+        V: PartialOrd + fmt::Debug,
+    {
+        self.compare("<", bound, |value, operand| value < operand)
+    }
+
+    /// Match values less than or equal to `bound`
+    pub fn le(self, bound: V) -> KeyPathPredicate<T>
+    where
+        V: PartialOrd + fmt::Debug,
+    {
+        self.compare("<=", bound, |value, operand| value <= operand)
+    }
+
+    /// Match values within the inclusive range `low..=high`
+    pub fn between(self, low: V, high: V) -> KeyPathPredicate<T>
+    where
+        V: PartialOrd + fmt::Debug,
+    {
+        let description = format!("between {:?} and {:?}", low, high);
+        self.matches(description, move |value| *value >= low && *value <= high)
+    }
+
+    /// Match values contained in `set`
+    ///
+    /// The description lists the values in the order given, without duplicates.
+    pub fn in_set<I>(self, set: I) -> KeyPathPredicate<T>
+    where
+        I: IntoIterator<Item = V>,
+        V: std::hash::Hash + Eq + fmt::Debug,
+    {
+        let mut values = HashSet::new();
+        let mut listed = Vec::new();
+        for value in set {
+            let formatted = format!("{:?}", value);
+            if values.insert(value) {
+                listed.push(formatted);
+            }
+        }
+        let description = format!("in {{{}}}", listed.join(", "));
+        self.matches(description, move |value| values.contains(value))
+    }
+
+    /// Match string values containing `needle`
+    pub fn contains(self, needle: impl Into<String>) -> KeyPathPredicate<T>
+    where
+        V: AsRef<str>,
+    {
+        let needle = needle.into();
+        let description = format!("contains {:?}", needle);
+        self.matches(description, move |value| value.as_ref().contains(needle.as_str()))
+    }
+
+    /// Match string values starting with `prefix`
+    pub fn starts_with(self, prefix: impl Into<String>) -> KeyPathPredicate<T>
+    where
+        V: AsRef<str>,
+    {
+        let prefix = prefix.into();
+        let description = format!("starts_with {:?}", prefix);
+        self.matches(description, move |value| value.as_ref().starts_with(prefix.as_str()))
+    }
+
+    /// Match string values ending with `suffix`
+    pub fn ends_with(self, suffix: impl Into<String>) -> KeyPathPredicate<T>
+    where
+        V: AsRef<str>,
+    {
+        let suffix = suffix.into();
+        let description = format!("ends_with {:?}", suffix);
+        self.matches(description, move |value| value.as_ref().ends_with(suffix.as_str()))
+    }
+}
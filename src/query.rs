@@ -0,0 +1,628 @@
+//! String-based query language compiled against registered keypaths
+//!
+//! Queries such as `age >= 30 && department == "Engineering"` are parsed at runtime and compiled
+//! into a [`KeyPathPredicate`] using a [`QueryRegistry`] that maps field names to keypaths.
+//!
+//! ## Syntax
+//!
+//! - Comparisons: `field == value`, `!=`, `<`, `<=`, `>`, `>=`
+//! - String matching: `field contains "x"`, `field starts_with "x"`, `field ends_with "x"`
+//! - Boolean logic: `&&`, `||`, `!` and parentheses
+//! - Literals: integers, floats, `"strings"` or `'strings'`, `true` and `false`
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use rust_prelude_plus::prelude::*;
+//! use rust_prelude_plus::query::QueryRegistry;
+//! use key_paths_derive::Keypath;
+//!
+//! #[derive(Keypath, Debug, Clone)]
+//! struct Employee {
+//!     name: String,
+//!     age: u32,
+//!     department: String,
+//! }
+//!
+//! let registry = QueryRegistry::new()
+//!     .register("name", Employee::name())
+//!     .register("age", Employee::age())
+//!     .register("department", Employee::department());
+//!
+//! let query = registry.compile(r#"age >= 30 && department == "Engineering""#).unwrap();
+//!
+//! let employees = vec![
+//!     Employee { name: "Alice".to_string(), age: 30, department: "Engineering".to_string() },
+//!     Employee { name: "Bob".to_string(), age: 25, department: "Engineering".to_string() },
+//!     Employee { name: "Charlie".to_string(), age: 35, department: "Marketing".to_string() },
+//! ];
+//!
+//! let count = employees.count_by_keypath(identity_keypath(), query.as_fn()).unwrap();
+//! assert_eq!(count, 1);
+//!
+//! let matching = filter_by_keypath(employees, identity_keypath(), query.as_fn()).unwrap();
+//! assert_eq!(matching[0].name, "Alice");
+//!
+//! let error = registry.compile("agee > 3").unwrap_err();
+//! assert!(error.to_string().contains("unknown field 'agee'"));
+//! ```
+
+use crate::error::{KeyPathError, KeyPathResult};
+use crate::predicate::KeyPathPredicate;
+use key_paths_core::KeyPaths;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// A value a query field can be compared against
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryValue {
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+}
+
+/// The type of a query value, used to type-check queries at compile time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryValueKind {
+    Number,
+    Str,
+    Bool,
+}
+
+impl QueryValue {
+    /// The kind of this value
+    pub fn kind(&self) -> QueryValueKind {
+        match self {
+            QueryValue::Int(_) | QueryValue::Float(_) => QueryValueKind::Number,
+            QueryValue::Str(_) => QueryValueKind::Str,
+            QueryValue::Bool(_) => QueryValueKind::Bool,
+        }
+    }
+
+    fn compare(&self, other: &QueryValue) -> Option<Ordering> {
+        match (self, other) {
+            (QueryValue::Int(a), QueryValue::Int(b)) => Some(a.cmp(b)),
+            (QueryValue::Int(a), QueryValue::Float(b)) => (*a as f64).partial_cmp(b),
+            (QueryValue::Float(a), QueryValue::Int(b)) => a.partial_cmp(&(*b as f64)),
+            (QueryValue::Float(a), QueryValue::Float(b)) => a.partial_cmp(b),
+            (QueryValue::Str(a), QueryValue::Str(b)) => Some(a.cmp(b)),
+            (QueryValue::Bool(a), QueryValue::Bool(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for QueryValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryValue::Int(value) => write!(f, "{}", value),
+            QueryValue::Float(value) => write!(f, "{}", value),
+            QueryValue::Str(value) => write!(f, "{:?}", value),
+            QueryValue::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl fmt::Display for QueryValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryValueKind::Number => f.write_str("number"),
+            QueryValueKind::Str => f.write_str("string"),
+            QueryValueKind::Bool => f.write_str("bool"),
+        }
+    }
+}
+
+/// Adapter converting field values into query values
+pub trait QueryValueAdapter {
+    /// The kind of value this type converts to
+    fn query_kind() -> QueryValueKind;
+
+    /// Convert the value for comparison
+    fn to_query_value(&self) -> QueryValue;
+}
+
+macro_rules! impl_query_value_adapter {
+    ($kind:ident, $variant:ident, $($ty:ty),*) => {
+        $(
+            impl QueryValueAdapter for $ty {
+                fn query_kind() -> QueryValueKind {
+                    QueryValueKind::$kind
+                }
+
+                fn to_query_value(&self) -> QueryValue {
+                    QueryValue::$variant((*self).into())
+                }
+            }
+        )*
+    };
+}
+
+impl_query_value_adapter!(Number, Int, i8, i16, i32, i64, u8, u16, u32, u64);
+impl_query_value_adapter!(Number, Float, f32, f64);
+impl_query_value_adapter!(Bool, Bool, bool);
+
+impl QueryValueAdapter for usize {
+    fn query_kind() -> QueryValueKind {
+        QueryValueKind::Number
+    }
+
+    fn to_query_value(&self) -> QueryValue {
+        QueryValue::Int(*self as i128)
+    }
+}
+
+impl QueryValueAdapter for isize {
+    fn query_kind() -> QueryValueKind {
+        QueryValueKind::Number
+    }
+
+    fn to_query_value(&self) -> QueryValue {
+        QueryValue::Int(*self as i128)
+    }
+}
+
+impl QueryValueAdapter for String {
+    fn query_kind() -> QueryValueKind {
+        QueryValueKind::Str
+    }
+
+    fn to_query_value(&self) -> QueryValue {
+        QueryValue::Str(self.clone())
+    }
+}
+
+impl QueryValueAdapter for char {
+    fn query_kind() -> QueryValueKind {
+        QueryValueKind::Str
+    }
+
+    fn to_query_value(&self) -> QueryValue {
+        QueryValue::Str(self.to_string())
+    }
+}
+
+/// Comparison operators supported by the query language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+impl CompareOp {
+    fn evaluate(self, left: &QueryValue, right: &QueryValue) -> bool {
+        match self {
+            CompareOp::Eq => left.compare(right) == Some(Ordering::Equal),
+            CompareOp::Ne => left.compare(right).is_some_and(|o| o != Ordering::Equal),
+            CompareOp::Lt => left.compare(right) == Some(Ordering::Less),
+            CompareOp::Le => matches!(left.compare(right), Some(Ordering::Less | Ordering::Equal)),
+            CompareOp::Gt => left.compare(right) == Some(Ordering::Greater),
+            CompareOp::Ge => matches!(left.compare(right), Some(Ordering::Greater | Ordering::Equal)),
+            CompareOp::Contains | CompareOp::StartsWith | CompareOp::EndsWith => {
+                match (left, right) {
+                    (QueryValue::Str(haystack), QueryValue::Str(needle)) => match self {
+                        CompareOp::Contains => haystack.contains(needle.as_str()),
+                        CompareOp::StartsWith => haystack.starts_with(needle.as_str()),
+                        _ => haystack.ends_with(needle.as_str()),
+                    },
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    fn is_string_op(self) -> bool {
+        matches!(self, CompareOp::Contains | CompareOp::StartsWith | CompareOp::EndsWith)
+    }
+
+    fn is_ordering_op(self) -> bool {
+        matches!(self, CompareOp::Lt | CompareOp::Le | CompareOp::Gt | CompareOp::Ge)
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Contains => "contains",
+            CompareOp::StartsWith => "starts_with",
+            CompareOp::EndsWith => "ends_with",
+        };
+        f.write_str(symbol)
+    }
+}
+
+/// Parsed query expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryExpr {
+    And(Box<QueryExpr>, Box<QueryExpr>),
+    Or(Box<QueryExpr>, Box<QueryExpr>),
+    Not(Box<QueryExpr>),
+    Compare {
+        field: String,
+        op: CompareOp,
+        value: QueryValue,
+        /// Byte offset of the field name in the query string
+        position: usize,
+    },
+}
+
+/// Parse a query string into an expression tree
+pub fn parse_query(query: &str) -> KeyPathResult<QueryExpr> {
+    let tokens = tokenize(query)?;
+    let mut parser = Parser { tokens, index: 0, end: query.len() };
+    let expr = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(query_error(
+            format!("unexpected {} after end of expression", token.kind),
+            token.position,
+        )),
+    }
+}
+
+fn query_error(message: impl Into<String>, position: usize) -> KeyPathError {
+    KeyPathError::QueryError {
+        message: message.into(),
+        position,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Literal(QueryValue),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Ident(name) => write!(f, "'{}'", name),
+            TokenKind::Literal(value) => write!(f, "{}", value),
+            TokenKind::Op(op) => write!(f, "'{}'", op),
+            TokenKind::And => f.write_str("'&&'"),
+            TokenKind::Or => f.write_str("'||'"),
+            TokenKind::Not => f.write_str("'!'"),
+            TokenKind::LParen => f.write_str("'('"),
+            TokenKind::RParen => f.write_str("')'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+}
+
+fn tokenize(query: &str) -> KeyPathResult<Vec<Token>> {
+    let chars: Vec<(usize, char)> = query.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (position, c) = chars[i];
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+
+        let (kind, consumed) = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => (TokenKind::LParen, 1),
+            ')' => (TokenKind::RParen, 1),
+            '&' if next == Some('&') => (TokenKind::And, 2),
+            '|' if next == Some('|') => (TokenKind::Or, 2),
+            '=' if next == Some('=') => (TokenKind::Op(CompareOp::Eq), 2),
+            '!' if next == Some('=') => (TokenKind::Op(CompareOp::Ne), 2),
+            '<' if next == Some('=') => (TokenKind::Op(CompareOp::Le), 2),
+            '>' if next == Some('=') => (TokenKind::Op(CompareOp::Ge), 2),
+            '!' => (TokenKind::Not, 1),
+            '<' => (TokenKind::Op(CompareOp::Lt), 1),
+            '>' => (TokenKind::Op(CompareOp::Gt), 1),
+            '=' => return Err(query_error("unexpected '=', did you mean '=='?", position)),
+            '&' => return Err(query_error("unexpected '&', did you mean '&&'?", position)),
+            '|' => return Err(query_error("unexpected '|', did you mean '||'?", position)),
+            '"' | '\'' => {
+                let quote = c;
+                let mut value = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => return Err(query_error("unterminated string literal", position)),
+                        Some(&(_, '\\')) => {
+                            match chars.get(j + 1) {
+                                Some(&(_, escaped)) => value.push(escaped),
+                                None => return Err(query_error("unterminated string literal", position)),
+                            }
+                            j += 2;
+                        }
+                        Some(&(_, ch)) if ch == quote => break,
+                        Some(&(_, ch)) => {
+                            value.push(ch);
+                            j += 1;
+                        }
+                    }
+                }
+                (TokenKind::Literal(QueryValue::Str(value)), j + 1 - i)
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let mut j = i + 1;
+                while j < chars.len() && (chars[j].1.is_ascii_digit() || chars[j].1 == '.') {
+                    j += 1;
+                }
+                let end = chars.get(j).map_or(query.len(), |&(p, _)| p);
+                let text = &query[position..end];
+                let value = if text.contains('.') {
+                    text.parse::<f64>().map(QueryValue::Float).ok()
+                } else {
+                    text.parse::<i128>().map(QueryValue::Int).ok()
+                };
+                match value {
+                    Some(value) => (TokenKind::Literal(value), j - i),
+                    None => return Err(query_error(format!("invalid number '{}'", text), position)),
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut j = i + 1;
+                while j < chars.len() && (chars[j].1.is_alphanumeric() || chars[j].1 == '_' || chars[j].1 == '.') {
+                    j += 1;
+                }
+                let end = chars.get(j).map_or(query.len(), |&(p, _)| p);
+                let kind = match &query[position..end] {
+                    "true" => TokenKind::Literal(QueryValue::Bool(true)),
+                    "false" => TokenKind::Literal(QueryValue::Bool(false)),
+                    "contains" => TokenKind::Op(CompareOp::Contains),
+                    "starts_with" => TokenKind::Op(CompareOp::StartsWith),
+                    "ends_with" => TokenKind::Op(CompareOp::EndsWith),
+                    ident => TokenKind::Ident(ident.to_string()),
+                };
+                (kind, j - i)
+            }
+            other => return Err(query_error(format!("unexpected character '{}'", other), position)),
+        };
+
+        tokens.push(Token { kind, position });
+        i += consumed;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn expect_more(&mut self, expected: &str) -> KeyPathResult<Token> {
+        let end = self.end;
+        self.advance()
+            .ok_or_else(|| query_error(format!("expected {}, found end of query", expected), end))
+    }
+
+    fn parse_or(&mut self) -> KeyPathResult<QueryExpr> {
+        let mut left = self.parse_and()?;
+        while matches!(self.peek(), Some(Token { kind: TokenKind::Or, .. })) {
+            self.advance();
+            let right = self.parse_and()?;
+            left = QueryExpr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> KeyPathResult<QueryExpr> {
+        let mut left = self.parse_unary()?;
+        while matches!(self.peek(), Some(Token { kind: TokenKind::And, .. })) {
+            self.advance();
+            let right = self.parse_unary()?;
+            left = QueryExpr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> KeyPathResult<QueryExpr> {
+        if matches!(self.peek(), Some(Token { kind: TokenKind::Not, .. })) {
+            self.advance();
+            let inner = self.parse_unary()?;
+            return Ok(QueryExpr::Not(Box::new(inner)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> KeyPathResult<QueryExpr> {
+        let token = self.expect_more("a field name or '('")?;
+        match token.kind {
+            TokenKind::LParen => {
+                let expr = self.parse_or()?;
+                let close = self.expect_more("')'")?;
+                match close.kind {
+                    TokenKind::RParen => Ok(expr),
+                    other => Err(query_error(format!("expected ')', found {}", other), close.position)),
+                }
+            }
+            TokenKind::Ident(field) => {
+                let op_token = self.expect_more(&format!("a comparison operator after '{}'", field))?;
+                let op = match op_token.kind {
+                    TokenKind::Op(op) => op,
+                    other => {
+                        return Err(query_error(
+                            format!("expected a comparison operator after '{}', found {}", field, other),
+                            op_token.position,
+                        ))
+                    }
+                };
+                let value_token = self.expect_more(&format!("a value after '{}'", op))?;
+                let value = match value_token.kind {
+                    TokenKind::Literal(value) => value,
+                    other => {
+                        return Err(query_error(
+                            format!("expected a value after '{}', found {}", op, other),
+                            value_token.position,
+                        ))
+                    }
+                };
+                Ok(QueryExpr::Compare { field, op, value, position: token.position })
+            }
+            other => Err(query_error(
+                format!("expected a field name or '(', found {}", other),
+                token.position,
+            )),
+        }
+    }
+}
+
+type QueryValueExtractor<T> = Rc<dyn Fn(&T) -> Option<QueryValue>>;
+
+struct QueryField<T> {
+    kind: Option<QueryValueKind>,
+    extract: QueryValueExtractor<T>,
+}
+
+/// Registry mapping field names to keypaths for query compilation
+pub struct QueryRegistry<T> {
+    fields: BTreeMap<String, QueryField<T>>,
+}
+
+impl<T: 'static> QueryRegistry<T> {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self { fields: BTreeMap::new() }
+    }
+
+    /// Register a field whose value type has a [`QueryValueAdapter`]
+    pub fn register<V>(mut self, name: impl Into<String>, keypath: KeyPaths<T, V>) -> Self
+    where
+        V: QueryValueAdapter + 'static,
+    {
+        let field = QueryField {
+            kind: Some(V::query_kind()),
+            extract: Rc::new(move |item: &T| keypath.get(item).map(V::to_query_value)),
+        };
+        self.fields.insert(name.into(), field);
+        self
+    }
+
+    /// Register a field with a custom value adapter
+    ///
+    /// Comparisons against fields registered this way are not type-checked at compile time;
+    /// mismatched comparisons simply do not match.
+    pub fn register_with<V, F>(mut self, name: impl Into<String>, keypath: KeyPaths<T, V>, adapter: F) -> Self
+    where
+        V: 'static,
+        F: Fn(&V) -> QueryValue + 'static,
+    {
+        let field = QueryField {
+            kind: None,
+            extract: Rc::new(move |item: &T| keypath.get(item).map(&adapter)),
+        };
+        self.fields.insert(name.into(), field);
+        self
+    }
+
+    /// Names of all registered fields, in sorted order
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.keys().map(String::as_str)
+    }
+
+    /// Parse and compile a query into a predicate
+    pub fn compile(&self, query: &str) -> KeyPathResult<KeyPathPredicate<T>> {
+        let expr = parse_query(query)?;
+        self.compile_expr(&expr)
+    }
+
+    /// Compile an already parsed expression into a predicate
+    pub fn compile_expr(&self, expr: &QueryExpr) -> KeyPathResult<KeyPathPredicate<T>> {
+        match expr {
+            QueryExpr::And(left, right) => Ok(self.compile_expr(left)?.and(self.compile_expr(right)?)),
+            QueryExpr::Or(left, right) => Ok(self.compile_expr(left)?.or(self.compile_expr(right)?)),
+            QueryExpr::Not(inner) => Ok(self.compile_expr(inner)?.not()),
+            QueryExpr::Compare { field, op, value, position } => {
+                let registered = self.fields.get(field).ok_or_else(|| {
+                    let known: Vec<&str> = self.field_names().collect();
+                    query_error(
+                        format!("unknown field '{}' (known fields: {})", field, known.join(", ")),
+                        *position,
+                    )
+                })?;
+
+                if let Some(kind) = registered.kind {
+                    check_comparison(field, kind, *op, value, *position)?;
+                }
+
+                let extract = Rc::clone(&registered.extract);
+                let (op, value) = (*op, value.clone());
+                let description = format!("{} {} {}", field, op, value);
+                Ok(KeyPathPredicate::new(description, move |item: &T| {
+                    extract(item).is_some_and(|actual| op.evaluate(&actual, &value))
+                }))
+            }
+        }
+    }
+}
+
+impl<T: 'static> Default for QueryRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check_comparison(
+    field: &str,
+    kind: QueryValueKind,
+    op: CompareOp,
+    value: &QueryValue,
+    position: usize,
+) -> KeyPathResult<()> {
+    if kind != value.kind() {
+        return Err(query_error(
+            format!("cannot compare {} field '{}' with {} value {}", kind, field, value.kind(), value),
+            position,
+        ));
+    }
+    if op.is_string_op() && kind != QueryValueKind::Str {
+        return Err(query_error(
+            format!("operator '{}' requires a string field, but '{}' is a {}", op, field, kind),
+            position,
+        ));
+    }
+    if op.is_ordering_op() && kind == QueryValueKind::Bool {
+        return Err(query_error(
+            format!("operator '{}' is not supported for bool field '{}'", op, field),
+            position,
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! Field metadata for structs deriving `KeyPathSchema`
+//!
+//! `#[derive(KeyPathSchema)]` generates the list of a struct's field names and types together
+//! with a type-erased keypath per field, so generic tooling (CSV export, the query DSL, diffs)
+//! can enumerate fields without registering them by hand. Fields marked
+//! `#[keypath_schema(nested)]` are flattened into dotted paths such as `address.city`.
+//!
+//! Supported field attributes:
+//!
+//! - `#[keypath_schema(skip)]`: leave the field out of the schema
+//! - `#[keypath_schema(rename = "name")]`: use a different field name
+//! - `#[keypath_schema(nested)]`: flatten the fields of a type that also derives `KeyPathSchema`
+//! - `#[keypath_schema(debug)]`: format values with `Debug` even when they implement `Display`
+//! - `#[keypath_schema(serialize)]`: make values serializable (requires the `serde` feature)
+//!
+//! Standard containers such as `Vec`, `Option` and maps are formatted with `Debug` automatically.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use rust_prelude_plus::prelude::*;
+//!
+//! #[derive(KeyPathSchema, Debug, Clone)]
+//! struct Address {
+//!     city: String,
+//!     zip: u32,
+//! }
+//!
+//! #[derive(KeyPathSchema, Debug, Clone)]
+//! struct Person {
+//!     name: String,
+//!     tags: Vec<String>,
+//!     #[keypath_schema(nested)]
+//!     address: Address,
+//! }
+//!
+//! assert_eq!(Person::FIELD_NAMES, &["name", "tags", "address"]);
+//! assert_eq!(Person::FIELD_TYPES, &["String", "Vec<String>", "Address"]);
+//! assert_eq!(Person::field_paths(), vec!["name", "tags", "address.city", "address.zip"]);
+//!
+//! let person = Person {
+//!     name: "Alice".to_string(),
+//!     tags: vec!["admin".to_string()],
+//!     address: Address { city: "Paris".to_string(), zip: 75001 },
+//! };
+//! let registry = Person::keypath_registry();
+//! let city = registry.get_value(&person, "address.city").unwrap();
+//! assert_eq!(city.to_string(), "Paris");
+//! ```
+
+use crate::dynamic::{AnyKeyPath, KeyPathRegistry};
+use key_paths_core::KeyPaths;
+
+pub use rust_prelude_plus_derive::KeyPathSchema;
+
+/// Name, declared type and type-erased keypath of one field
+pub struct FieldSchema<T> {
+    name: String,
+    type_name: &'static str,
+    keypath: AnyKeyPath<T>,
+}
+
+impl<T> FieldSchema<T> {
+    /// Describe a field
+    pub fn new(name: impl Into<String>, type_name: &'static str, keypath: AnyKeyPath<T>) -> Self {
+        Self {
+            name: name.into(),
+            type_name,
+            keypath,
+        }
+    }
+
+    /// Field name, dotted for nested fields
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Field type as written in the struct definition
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Type-erased keypath to the field
+    pub fn keypath(&self) -> &AnyKeyPath<T> {
+        &self.keypath
+    }
+
+    /// Consume the field description, returning its keypath
+    pub fn into_keypath(self) -> AnyKeyPath<T> {
+        self.keypath
+    }
+
+    /// Lift the field to a parent type, prefixing its name with `prefix.`
+    pub fn nested<R>(self, prefix: &str, keypath: KeyPaths<R, T>) -> FieldSchema<R>
+    where
+        T: 'static,
+        R: 'static,
+    {
+        FieldSchema {
+            name: format!("{}.{}", prefix, self.name),
+            type_name: self.type_name,
+            keypath: self.keypath.at(keypath),
+        }
+    }
+}
+
+impl<T> Clone for FieldSchema<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            type_name: self.type_name,
+            keypath: self.keypath.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for FieldSchema<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FieldSchema")
+            .field("name", &self.name)
+            .field("type_name", &self.type_name)
+            .finish()
+    }
+}
+
+/// Static field metadata for a struct, usually derived with `#[derive(KeyPathSchema)]`
+pub trait KeyPathSchema: Sized + 'static {
+    /// Names of the top-level fields, in declaration order
+    const FIELD_NAMES: &'static [&'static str];
+
+    /// Types of the top-level fields, as written in the struct definition
+    const FIELD_TYPES: &'static [&'static str];
+
+    /// Every field, with nested fields flattened into dotted paths
+    fn schema() -> Vec<FieldSchema<Self>>;
+
+    /// Names of every field, with nested fields flattened into dotted paths
+    fn field_paths() -> Vec<String> {
+        Self::schema().into_iter().map(|field| field.name).collect()
+    }
+
+    /// A registry of every field, keyed by its (dotted) name
+    fn keypath_registry() -> KeyPathRegistry<Self> {
+        Self::schema()
+            .into_iter()
+            .fold(KeyPathRegistry::new(), |registry, field| {
+                registry.register_any(field.name, field.keypath)
+            })
+    }
+}
@@ -20,7 +20,8 @@
 //! }
 //!
 //! let person = Rc::new(Person { name: "Alice".to_string(), age: 30 });
-//! let name = person.get_at_keypath(Person::name()).unwrap();
+//! let name_keypath = Person::name();
+//! let name = person.get_at_keypath(&name_keypath).unwrap();
 //! assert_eq!(name, "Alice");
 //! ```
 //!
@@ -42,22 +43,104 @@
 //!     Rc::new(Product { name: "Book".to_string(), price: 19.99 }),
 //! ];
 //!
-//! // Lazy evaluation with iterator extensions
+//! // Filtering returns the `Rc` handles rather than cloned products
 //! let expensive_products: Vec<Rc<Product>> = products
-//!     .iter()
-//!     .filter_by_keypath(Product::price(), |&price| price > 100.0)
-//!     .map(|item| item.clone())
-//!     .collect();
+//!     .into_iter()
+//!     .filter_by_keypath(Product::price(), |&price| price > 100.0);
+//! assert_eq!(expensive_products[0].name, "Laptop");
 //! ```
 
 use key_paths_core::KeyPaths;
-use crate::error::{KeyPathResult, KeyPathError};
+use crate::access::{AccessOutput, KeyPathAccess, OwnedKeyPathAccess};
+use crate::collections::utils::select_ranked;
+use crate::error::{closure_error, ErrorCollector, ErrorMode, KeyPathResult, KeyPathError};
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Types that give access to a keypath root of type `T`
+///
+/// Lets a `KeyPaths<T, V>` be applied to `T` itself as well as to `&T`, `Rc<T>`, `Arc<T>`,
+/// `Box<T>` and `Cow<T>`, and to references to those smart pointers as yielded by `iter()`.
+pub trait KeyPathRoot<T> {
+    /// Borrow the keypath root
+    fn keypath_root(&self) -> &T;
+}
+
+impl<T> KeyPathRoot<T> for T {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T> KeyPathRoot<T> for &T {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T> KeyPathRoot<T> for &mut T {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T> KeyPathRoot<T> for Box<T> {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T> KeyPathRoot<T> for Rc<T> {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T> KeyPathRoot<T> for Arc<T> {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T: Clone> KeyPathRoot<T> for Cow<'_, T> {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T> KeyPathRoot<T> for &Box<T> {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T> KeyPathRoot<T> for &Rc<T> {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T> KeyPathRoot<T> for &Arc<T> {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
+
+impl<T: Clone> KeyPathRoot<T> for &Cow<'_, T> {
+    fn keypath_root(&self) -> &T {
+        self
+    }
+}
 
 /// Trait for types that can be operated on with keypaths
 pub trait KeyPathsOperable: Sized {
-    /// Get a value at a keypath
-    fn get_at_keypath<'a, V>(&'a self, keypath: &'a KeyPaths<Self, V>) -> KeyPathResult<&'a V> {
-        keypath.get(self).ok_or_else(|| KeyPathError::InvalidAccess { 
+    /// Get a value at a keypath, looking through smart pointers to the keypath root
+    fn get_at_keypath<'a, T, V>(&'a self, keypath: &'a KeyPaths<T, V>) -> KeyPathResult<&'a V>
+    where
+        Self: KeyPathRoot<T>,
+    {
+        keypath.get(self.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { 
             message: "KeyPath access failed".to_string() 
         })
     }
@@ -75,14 +158,15 @@ pub trait KeyPathsOperable: Sized {
 /// Trait for iterators that support keypath operations
 pub trait KeyPathsIterator: Iterator {
     /// Map over a keypath in the iterator
-    fn map_keypath<V, F, R>(self, keypath: KeyPaths<Self::Item, V>, f: F) -> Vec<R>
+    fn map_keypath<T, K, F, R>(self, keypath: K, f: F) -> Vec<R>
     where
         Self: Sized,
-        Self::Item: KeyPathsOperable,
-        F: Fn(&V) -> R,
+        Self::Item: KeyPathRoot<T>,
+        K: KeyPathAccess<T>,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> R,
     {
         self.map(|item| {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in map_keypath")
             });
             f(value)
@@ -90,14 +174,15 @@ pub trait KeyPathsIterator: Iterator {
     }
     
     /// Filter by a keypath predicate
-    fn filter_by_keypath<V, F>(self, keypath: KeyPaths<Self::Item, V>, predicate: F) -> Vec<Self::Item>
+    fn filter_by_keypath<T, K, F>(self, keypath: K, predicate: F) -> Vec<Self::Item>
     where
         Self: Sized,
-        Self::Item: KeyPathsOperable,
-        F: Fn(&V) -> bool,
+        Self::Item: KeyPathRoot<T>,
+        K: KeyPathAccess<T>,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool,
     {
         self.filter(|item| {
-            let value = item.get_at_keypath(&keypath).unwrap_or_else(|_| {
+            let value = keypath.access(item.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in filter_by_keypath")
             });
             predicate(value)
@@ -105,32 +190,32 @@ pub trait KeyPathsIterator: Iterator {
     }
     
     /// Find an element by keypath predicate
-    fn find_by_keypath<V, F>(self, keypath: KeyPaths<Self::Item, V>, predicate: F) -> KeyPathResult<Option<Self::Item>>
+    fn find_by_keypath<T, K, F>(self, keypath: K, predicate: F) -> KeyPathResult<Option<Self::Item>>
     where
         Self: Sized,
-        Self::Item: KeyPathsOperable,
-        F: Fn(&V) -> bool,
+        Self::Item: KeyPathRoot<T>,
+        K: KeyPathAccess<T>,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> bool,
     {
         for item in self {
-            if let Ok(value) = item.get_at_keypath(&keypath) {
-                if predicate(value) {
-                    return Ok(Some(item));
-                }
+            if keypath.try_access(item.keypath_root())?.is_some_and(&predicate) {
+                return Ok(Some(item));
             }
         }
         Ok(None)
     }
     
     /// Fold over a keypath
-    fn fold_keypath<V, F, B>(self, keypath: KeyPaths<Self::Item, V>, init: B, mut f: F) -> KeyPathResult<B>
+    fn fold_keypath<T, K, F, B>(self, keypath: K, init: B, mut f: F) -> KeyPathResult<B>
     where
         Self: Sized,
-        Self::Item: KeyPathsOperable,
-        F: FnMut(B, &V) -> B,
+        Self::Item: KeyPathRoot<T>,
+        K: KeyPathAccess<T>,
+        F: for<'a> FnMut(B, AccessOutput<'a, K, T>) -> B,
     {
         let mut acc = init;
         for item in self {
-            if let Ok(value) = item.get_at_keypath(&keypath) {
+            if let Some(value) = keypath.try_access(item.keypath_root())? {
                 acc = f(acc, value);
             }
         }
@@ -138,42 +223,156 @@ pub trait KeyPathsIterator: Iterator {
     }
     
     /// Collect values from a keypath
-    fn collect_keypath<V>(self, keypath: KeyPaths<Self::Item, V>) -> KeyPathResult<Vec<V>>
+    fn collect_keypath<T, K>(self, keypath: K) -> KeyPathResult<Vec<K::Value>>
     where
         Self: Sized,
-        Self::Item: KeyPathsOperable,
-        V: Clone,
+        Self::Item: KeyPathRoot<T>,
+        K: OwnedKeyPathAccess<T>,
     {
         let mut result = Vec::new();
         for item in self {
-            if let Ok(value) = item.get_at_keypath(&keypath) {
-                result.push(value.clone());
+            if let Some(value) = keypath.try_access_owned(item.keypath_root())? {
+                result.push(value);
             }
         }
         Ok(result)
     }
+    
+    /// The `k` items with the largest keypath values, largest first
+    ///
+    /// Keeps at most `k` items in a bounded heap, so this is O(n log k). Ties keep the earlier item.
+    fn top_k_by_keypath<T, K, F>(self, keypath: K, k: usize, compare: F) -> Vec<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: KeyPathRoot<T>,
+        K: OwnedKeyPathAccess<T>,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering,
+    {
+        let entries = self.enumerate().filter_map(|(index, item)| {
+            let value = keypath.try_access_owned(item.keypath_root()).unwrap_or_else(|error| {
+                panic!("KeyPath access failed in top_k_by_keypath: {}", error)
+            })?;
+            Some((value, index, item))
+        });
+        select_ranked(entries, k, &|a: &K::Value, b: &K::Value| compare(b, a))
+            .into_iter()
+            .map(|(_, _, item)| item)
+            .collect()
+    }
+    
+    /// The `k` items with the smallest keypath values, smallest first
+    fn bottom_k_by_keypath<T, K, F>(self, keypath: K, k: usize, compare: F) -> Vec<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: KeyPathRoot<T>,
+        K: OwnedKeyPathAccess<T>,
+        F: Fn(&K::Value, &K::Value) -> std::cmp::Ordering,
+    {
+        let entries = self.enumerate().filter_map(|(index, item)| {
+            let value = keypath.try_access_owned(item.keypath_root()).unwrap_or_else(|error| {
+                panic!("KeyPath access failed in bottom_k_by_keypath: {}", error)
+            })?;
+            Some((value, index, item))
+        });
+        select_ranked(entries, k, &compare)
+            .into_iter()
+            .map(|(_, _, item)| item)
+            .collect()
+    }
+    
+    /// Map over a keypath with a fallible closure
+    ///
+    /// Items are pulled one at a time, so with `ErrorMode::FailFast` the iterator is not advanced
+    /// past the first failing item.
+    fn try_map_keypath<T, K, F, R, E>(self, keypath: K, f: F, mode: ErrorMode) -> KeyPathResult<Vec<R>>
+    where
+        Self: Sized,
+        Self::Item: KeyPathRoot<T>,
+        K: KeyPathAccess<T>,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> Result<R, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut errors = ErrorCollector::new(mode);
+        let mut result = Vec::new();
+        for (index, item) in self.enumerate() {
+            let outcome = keypath
+                .access(item.keypath_root())
+                .ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })
+                .and_then(|value| f(value).map_err(|error| closure_error(index, error)));
+            match outcome {
+                Ok(value) => result.push(value),
+                Err(error) => errors.record(error)?,
+            }
+        }
+        errors.finish(result)
+    }
+    
+    /// Filter by a fallible keypath predicate
+    fn try_filter_by_keypath<T, K, F, E>(self, keypath: K, predicate: F, mode: ErrorMode) -> KeyPathResult<Vec<Self::Item>>
+    where
+        Self: Sized,
+        Self::Item: KeyPathRoot<T>,
+        K: KeyPathAccess<T>,
+        F: for<'a> Fn(AccessOutput<'a, K, T>) -> Result<bool, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut errors = ErrorCollector::new(mode);
+        let mut result = Vec::new();
+        for (index, item) in self.enumerate() {
+            let keep = keypath
+                .access(item.keypath_root())
+                .ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })
+                .and_then(|value| predicate(value).map_err(|error| closure_error(index, error)));
+            match keep {
+                Ok(true) => result.push(item),
+                Ok(false) => {}
+                Err(error) => errors.record(error)?,
+            }
+        }
+        errors.finish(result)
+    }
+    
+    /// Fold over a keypath with a fallible closure, stopping at the first error
+    fn try_fold_keypath<T, K, F, B, E>(self, keypath: K, init: B, f: F) -> KeyPathResult<B>
+    where
+        Self: Sized,
+        Self::Item: KeyPathRoot<T>,
+        K: KeyPathAccess<T>,
+        F: for<'a> Fn(B, AccessOutput<'a, K, T>) -> Result<B, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut acc = init;
+        for (index, item) in self.enumerate() {
+            let value = keypath.access(item.keypath_root()).ok_or_else(|| KeyPathError::InvalidAccess { message: "KeyPath access failed".to_string() })?;
+            acc = f(acc, value).map_err(|error| closure_error(index, error))?;
+        }
+        Ok(acc)
+    }
 }
 
 /// Trait for collections that support keypath operations
 pub trait KeyPathsCollection<T> {
     /// Group elements by keypath values
-    fn group_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, f: F) -> KeyPathResult<std::collections::HashMap<V, Vec<T>>>
+    fn group_by_keypath<R, K, F>(&self, keypath: K, f: F) -> KeyPathResult<std::collections::HashMap<K::Value, Vec<T>>>
     where
-        V: std::hash::Hash + Eq + Clone,
-        T: Clone + KeyPathsOperable,
-        F: Fn(&V) -> V;
+        K: KeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq + Clone,
+        T: Clone + KeyPathRoot<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> K::Value;
     
     /// Partition elements by keypath predicate
-    fn partition_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<(Vec<T>, Vec<T>)>
+    fn partition_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<(Vec<T>, Vec<T>)>
     where
-        T: Clone + KeyPathsOperable,
-        F: Fn(&V) -> bool;
+        T: Clone + KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool;
     
     /// Sort elements by keypath values
-    fn sort_by_keypath<V, F>(&mut self, keypath: KeyPaths<T, V>, compare: F) -> KeyPathResult<()>
+    fn sort_by_keypath<R, K, F>(&mut self, keypath: K, compare: F) -> KeyPathResult<()>
     where
-        T: KeyPathsOperable,
-        F: Fn(&V, &V) -> std::cmp::Ordering;
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, AccessOutput<'a, K, R>) -> std::cmp::Ordering;
 }
 
 // Implement KeyPathsOperable for all types
@@ -184,15 +383,16 @@ impl<I: Iterator> KeyPathsIterator for I {}
 
 // Implement KeyPathsCollection for Vec
 impl<T> KeyPathsCollection<T> for Vec<T> {
-    fn group_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, f: F) -> KeyPathResult<std::collections::HashMap<V, Vec<T>>>
+    fn group_by_keypath<R, K, F>(&self, keypath: K, f: F) -> KeyPathResult<std::collections::HashMap<K::Value, Vec<T>>>
     where
-        V: std::hash::Hash + Eq + Clone,
-        T: Clone + KeyPathsOperable,
-        F: Fn(&V) -> V,
+        K: KeyPathAccess<R>,
+        K::Value: std::hash::Hash + Eq + Clone,
+        T: Clone + KeyPathRoot<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> K::Value,
     {
         let mut groups = std::collections::HashMap::new();
         for item in self {
-            if let Ok(value) = item.get_at_keypath(&keypath) {
+            if let Some(value) = keypath.try_access(item.keypath_root())? {
                 let key = f(value);
                 groups.entry(key).or_insert_with(Vec::new).push(item.clone());
             }
@@ -200,16 +400,17 @@ impl<T> KeyPathsCollection<T> for Vec<T> {
         Ok(groups)
     }
     
-    fn partition_by_keypath<V, F>(&self, keypath: KeyPaths<T, V>, predicate: F) -> KeyPathResult<(Vec<T>, Vec<T>)>
+    fn partition_by_keypath<R, K, F>(&self, keypath: K, predicate: F) -> KeyPathResult<(Vec<T>, Vec<T>)>
     where
-        T: Clone + KeyPathsOperable,
-        F: Fn(&V) -> bool,
+        T: Clone + KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>) -> bool,
     {
         let mut left = Vec::new();
         let mut right = Vec::new();
         
         for item in self {
-            if let Ok(value) = item.get_at_keypath(&keypath) {
+            if let Some(value) = keypath.try_access(item.keypath_root())? {
                 if predicate(value) {
                     left.push(item.clone());
                 } else {
@@ -221,16 +422,17 @@ impl<T> KeyPathsCollection<T> for Vec<T> {
         Ok((left, right))
     }
     
-    fn sort_by_keypath<V, F>(&mut self, keypath: KeyPaths<T, V>, compare: F) -> KeyPathResult<()>
+    fn sort_by_keypath<R, K, F>(&mut self, keypath: K, compare: F) -> KeyPathResult<()>
     where
-        T: KeyPathsOperable,
-        F: Fn(&V, &V) -> std::cmp::Ordering,
+        T: KeyPathRoot<R>,
+        K: KeyPathAccess<R>,
+        F: for<'a> Fn(AccessOutput<'a, K, R>, AccessOutput<'a, K, R>) -> std::cmp::Ordering,
     {
         self.sort_by(|a, b| {
-            let a_val = keypath.get(a).unwrap_or_else(|| {
+            let a_val = keypath.access(a.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in sort")
             });
-            let b_val = keypath.get(b).unwrap_or_else(|| {
+            let b_val = keypath.access(b.keypath_root()).unwrap_or_else(|| {
                 panic!("KeyPath access failed in sort")
             });
             compare(a_val, b_val)
@@ -0,0 +1,345 @@
+//! Traversal keypaths that reach every element of a nested collection
+//!
+//! A `KeyPaths<T, V>` points at exactly one value. A `KeyPathTraversal<T, V>` points at zero or
+//! more: every element of a `Vec`, every value of a `HashMap`, the contents of an `Option`, and
+//! anything reached from those through further keypaths. Traversals are built from a keypath to a
+//! container with `each()`, and keypaths to a single element are built with `index()` and
+//! `key()`.
+//!
+//! Elements a keypath cannot reach (an empty `Option`, a failable keypath returning `None`) are
+//! skipped rather than reported as errors.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use rust_prelude_plus::prelude::*;
+//! use key_paths_core::KeyPaths;
+//! use key_paths_derive::Keypath;
+//!
+//! #[derive(Keypath, Debug, Clone)]
+//! struct Product {
+//!     name: String,
+//!     price: f64,
+//! }
+//!
+//! #[derive(Debug, Clone)]
+//! struct Order {
+//!     products: Vec<Product>,
+//! }
+//!
+//! let products = KeyPaths::readable(|order: &Order| &order.products);
+//! let orders = vec![
+//!     Order { products: vec![Product { name: "Laptop".to_string(), price: 999.0 }] },
+//!     Order {
+//!         products: vec![
+//!             Product { name: "Mouse".to_string(), price: 25.0 },
+//!             Product { name: "Pad".to_string(), price: 10.0 },
+//!         ],
+//!     },
+//! ];
+//!
+//! // Every product price across every order
+//! let prices = flatten_keypath(&orders, products.clone().each().then(Product::price())).unwrap();
+//! assert_eq!(prices, vec![&999.0, &25.0, &10.0]);
+//!
+//! // A single element
+//! let first = products.index(1);
+//! assert!(first.get(&orders[0]).is_none());
+//! assert_eq!(first.get(&orders[1]).unwrap().name, "Pad");
+//! ```
+
+use key_paths_core::KeyPaths;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::rc::Rc;
+
+type TraverseFn<T, V> = Rc<dyn for<'a> Fn(&'a T) -> Box<dyn Iterator<Item = &'a V> + 'a>>;
+
+/// Containers whose elements can be traversed by reference
+pub trait Traversable {
+    /// Element type reached by the traversal
+    type Item;
+
+    /// Iterate over every element
+    fn traverse<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Self::Item> + 'a>;
+}
+
+/// Containers whose elements can be addressed by position
+pub trait IndexTraversable: Traversable {
+    /// Element at `index`, if present
+    fn at_index(&self, index: usize) -> Option<&Self::Item>;
+}
+
+/// Containers whose elements can be addressed by key
+pub trait KeyTraversable: Traversable {
+    /// Key type used to address elements
+    type Key;
+
+    /// Element stored under `key`, if present
+    fn at_key(&self, key: &Self::Key) -> Option<&Self::Item>;
+}
+
+impl<V> Traversable for Vec<V> {
+    type Item = V;
+
+    fn traverse<'a>(&'a self) -> Box<dyn Iterator<Item = &'a V> + 'a> {
+        Box::new(self.iter())
+    }
+}
+
+impl<V> IndexTraversable for Vec<V> {
+    fn at_index(&self, index: usize) -> Option<&V> {
+        self.get(index)
+    }
+}
+
+impl<V> Traversable for VecDeque<V> {
+    type Item = V;
+
+    fn traverse<'a>(&'a self) -> Box<dyn Iterator<Item = &'a V> + 'a> {
+        Box::new(self.iter())
+    }
+}
+
+impl<V> IndexTraversable for VecDeque<V> {
+    fn at_index(&self, index: usize) -> Option<&V> {
+        self.get(index)
+    }
+}
+
+impl<V, const N: usize> Traversable for [V; N] {
+    type Item = V;
+
+    fn traverse<'a>(&'a self) -> Box<dyn Iterator<Item = &'a V> + 'a> {
+        Box::new(self.iter())
+    }
+}
+
+impl<V, const N: usize> IndexTraversable for [V; N] {
+    fn at_index(&self, index: usize) -> Option<&V> {
+        self.get(index)
+    }
+}
+
+impl<V> Traversable for Option<V> {
+    type Item = V;
+
+    fn traverse<'a>(&'a self) -> Box<dyn Iterator<Item = &'a V> + 'a> {
+        Box::new(self.iter())
+    }
+}
+
+impl<K, V, S> Traversable for HashMap<K, V, S> {
+    type Item = V;
+
+    fn traverse<'a>(&'a self) -> Box<dyn Iterator<Item = &'a V> + 'a> {
+        Box::new(self.values())
+    }
+}
+
+impl<K, V, S> KeyTraversable for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: std::hash::BuildHasher,
+{
+    type Key = K;
+
+    fn at_key(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+}
+
+impl<K, V> Traversable for BTreeMap<K, V> {
+    type Item = V;
+
+    fn traverse<'a>(&'a self) -> Box<dyn Iterator<Item = &'a V> + 'a> {
+        Box::new(self.values())
+    }
+}
+
+impl<K: Ord, V> KeyTraversable for BTreeMap<K, V> {
+    type Key = K;
+
+    fn at_key(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+}
+
+impl<V, S> Traversable for HashSet<V, S> {
+    type Item = V;
+
+    fn traverse<'a>(&'a self) -> Box<dyn Iterator<Item = &'a V> + 'a> {
+        Box::new(self.iter())
+    }
+}
+
+impl<V> Traversable for BTreeSet<V> {
+    type Item = V;
+
+    fn traverse<'a>(&'a self) -> Box<dyn Iterator<Item = &'a V> + 'a> {
+        Box::new(self.iter())
+    }
+}
+
+/// Read a keypath without tying the result to the keypath's own borrow
+///
+/// `KeyPaths::get` borrows the keypath for as long as the returned reference lives, which
+/// would keep a keypath captured inside a traversal iterator borrowed past the closure call.
+pub(crate) fn project<'a, T, V>(keypath: &KeyPaths<T, V>, root: &'a T) -> Option<&'a V> {
+    match keypath {
+        KeyPaths::Readable(f) => Some(f(root)),
+        KeyPaths::FailableReadable(f) => f(root),
+        KeyPaths::ReadableEnum { extract, .. } => extract(root),
+        KeyPaths::WritableEnum { extract, .. } => extract(root),
+        KeyPaths::FailableCombined { readable, .. } => readable(root),
+        _ => None,
+    }
+}
+
+/// A keypath reaching zero or more values of type `V` inside a `T`
+pub struct KeyPathTraversal<T, V> {
+    traverse: TraverseFn<T, V>,
+}
+
+impl<T, V> Clone for KeyPathTraversal<T, V> {
+    fn clone(&self) -> Self {
+        Self {
+            traverse: self.traverse.clone(),
+        }
+    }
+}
+
+impl<T, V> std::fmt::Debug for KeyPathTraversal<T, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyPathTraversal")
+            .field("root", &std::any::type_name::<T>())
+            .field("value", &std::any::type_name::<V>())
+            .finish()
+    }
+}
+
+impl<T: 'static, V: 'static> KeyPathTraversal<T, V> {
+    /// Create a traversal from a function yielding the values reached from a root
+    pub fn new(traverse: impl for<'a> Fn(&'a T) -> Box<dyn Iterator<Item = &'a V> + 'a> + 'static) -> Self {
+        Self {
+            traverse: Rc::new(traverse),
+        }
+    }
+
+    /// A traversal reaching the single value of a keypath, or nothing if it is absent
+    pub fn from_keypath(keypath: KeyPaths<T, V>) -> Self {
+        Self::new(move |root| Box::new(project(&keypath, root).into_iter()))
+    }
+
+    /// Iterate over every value reached from `root`
+    pub fn iter<'a>(&self, root: &'a T) -> Box<dyn Iterator<Item = &'a V> + 'a> {
+        (self.traverse)(root)
+    }
+
+    /// Collect every value reached from `root`
+    pub fn get_all<'a>(&self, root: &'a T) -> Vec<&'a V> {
+        self.iter(root).collect()
+    }
+
+    /// Number of values reached from `root`
+    pub fn count(&self, root: &T) -> usize {
+        self.iter(root).count()
+    }
+
+    /// Follow a keypath from every value, skipping values where it is absent
+    pub fn then<U: 'static>(self, keypath: KeyPaths<V, U>) -> KeyPathTraversal<T, U> {
+        let traverse = self.traverse;
+        KeyPathTraversal::new(move |root| {
+            let keypath = keypath.clone();
+            Box::new(traverse(root).filter_map(move |value| project(&keypath, value)))
+        })
+    }
+
+    /// Follow another traversal from every value
+    pub fn then_traversal<U: 'static>(self, next: KeyPathTraversal<V, U>) -> KeyPathTraversal<T, U> {
+        let traverse = self.traverse;
+        KeyPathTraversal::new(move |root| {
+            let next = next.clone();
+            Box::new(traverse(root).flat_map(move |value| next.iter(value)))
+        })
+    }
+
+    /// Traverse every element of every container reached so far
+    pub fn each(self) -> KeyPathTraversal<T, V::Item>
+    where
+        V: Traversable,
+        V::Item: 'static,
+    {
+        let traverse = self.traverse;
+        KeyPathTraversal::new(move |root| Box::new(traverse(root).flat_map(|container| container.traverse())))
+    }
+
+    /// Keep only the values matching a predicate
+    pub fn filter<F>(self, predicate: F) -> Self
+    where
+        F: Fn(&V) -> bool + 'static,
+    {
+        let traverse = self.traverse;
+        let predicate = Rc::new(predicate);
+        Self::new(move |root| {
+            let predicate = predicate.clone();
+            Box::new(traverse(root).filter(move |value| predicate(value)))
+        })
+    }
+}
+
+impl<T: 'static, V: 'static> From<KeyPaths<T, V>> for KeyPathTraversal<T, V> {
+    fn from(keypath: KeyPaths<T, V>) -> Self {
+        Self::from_keypath(keypath)
+    }
+}
+
+/// Traversal constructors for keypaths pointing at a container
+pub trait KeyPathsTraversalExt<T, C> {
+    /// Traverse every element of the container
+    fn each(self) -> KeyPathTraversal<T, C::Item>
+    where
+        C: Traversable,
+        C::Item: 'static;
+
+    /// Keypath to the element at `index`, absent when out of bounds
+    fn index(self, index: usize) -> KeyPaths<T, C::Item>
+    where
+        C: IndexTraversable,
+        C::Item: 'static;
+
+    /// Keypath to the element stored under `key`, absent when the key is missing
+    fn key(self, key: C::Key) -> KeyPaths<T, C::Item>
+    where
+        C: KeyTraversable,
+        C::Key: 'static,
+        C::Item: 'static;
+
+}
+
+impl<T: 'static, C: 'static> KeyPathsTraversalExt<T, C> for KeyPaths<T, C> {
+    fn each(self) -> KeyPathTraversal<T, C::Item>
+    where
+        C: Traversable,
+        C::Item: 'static,
+    {
+        KeyPathTraversal::from_keypath(self).each()
+    }
+
+    fn index(self, index: usize) -> KeyPaths<T, C::Item>
+    where
+        C: IndexTraversable,
+        C::Item: 'static,
+    {
+        self.then(KeyPaths::failable_readable(move |container: &C| container.at_index(index)))
+    }
+
+    fn key(self, key: C::Key) -> KeyPaths<T, C::Item>
+    where
+        C: KeyTraversable,
+        C::Key: 'static,
+        C::Item: 'static,
+    {
+        self.then(KeyPaths::failable_readable(move |container: &C| container.at_key(&key)))
+    }
+}
@@ -0,0 +1,163 @@
+use key_paths_derive::Keypath;
+use rust_prelude_plus::prelude::*;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Task {
+    id: u32,
+    title: String,
+    estimate: u32,
+    owner: Option<String>,
+}
+
+fn task(id: u32, title: &str, estimate: u32, owner: Option<&str>) -> Task {
+    Task {
+        id,
+        title: title.to_string(),
+        estimate,
+        owner: owner.map(str::to_string),
+    }
+}
+
+fn tasks() -> Vec<Task> {
+    vec![
+        task(1, "write docs", 3, Some("ann")),
+        task(2, "fix build", 1, Some("bob")),
+        task(3, "review", 2, None),
+        task(4, "release", 1, Some("ann")),
+        task(5, "triage", 5, Some("bob")),
+    ]
+}
+
+fn ids(tasks: &[Task]) -> Vec<u32> {
+    tasks.iter().map(|task| task.id).collect()
+}
+
+/// A computed keypath that counts how often it is evaluated
+fn counted_estimate(calls: &Arc<AtomicUsize>) -> ComputedKeyPath<Task, u32> {
+    let calls = Arc::clone(calls);
+    computed_keypath(move |task: &Task| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        task.estimate
+    })
+}
+
+#[test]
+fn test_sort_by_cached_keypath_reads_each_value_once() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut sorted = tasks();
+    sorted.sort_by_cached_keypath(counted_estimate(&calls), |a, b| a.cmp(b)).unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 5);
+    // Stable: tasks 2 and 4 share an estimate and keep their order
+    assert_eq!(ids(&sorted), vec![2, 4, 3, 1, 5]);
+
+    let mut expected = tasks();
+    sort_by_keypath(&mut expected, Task::estimate(), |a, b| a.cmp(b)).unwrap();
+    assert_eq!(sorted, expected);
+
+    let mut deque: VecDeque<Task> = tasks().into_iter().collect();
+    deque.rotate_left(2);
+    deque.sort_by_cached_keypath(Task::title(), |a, b| b.cmp(a)).unwrap();
+    assert_eq!(deque.iter().map(|task| task.id).collect::<Vec<_>>(), vec![1, 5, 3, 4, 2]);
+}
+
+#[test]
+fn test_sort_by_cached_keypath_matches_std_sort() {
+    let mut values: Vec<Task> = (0..200)
+        .map(|i| task(i, &format!("t{}", (i * 7919) % 200), (i * 37) % 11, None))
+        .collect();
+    let mut expected = values.clone();
+    expected.sort_by(|a, b| a.estimate.cmp(&b.estimate).then_with(|| b.title.cmp(&a.title)));
+
+    let key = Task::estimate().zip(Task::title());
+    sort_by_cached_keypath(&mut values, key, |(e1, t1), (e2, t2)| e1.cmp(e2).then_with(|| t2.cmp(t1))).unwrap();
+    assert_eq!(values, expected);
+}
+
+#[test]
+fn test_sort_by_cached_keypath_takes_the_same_comparator() {
+    fn by_title(a: &String, b: &String) -> std::cmp::Ordering {
+        a.cmp(b)
+    }
+    let mut cached = tasks();
+    let mut plain = tasks();
+    cached.sort_by_cached_keypath(Task::title(), by_title).unwrap();
+    sort_by_keypath(&mut plain, Task::title(), by_title).unwrap();
+    assert_eq!(cached, plain);
+    assert_eq!(ids(&cached), vec![2, 4, 3, 5, 1]);
+}
+
+#[test]
+fn test_sort_by_cached_keypath_leaves_collection_on_absent_value() {
+    let mut unsorted = tasks();
+    let result = unsorted.sort_by_cached_keypath(Task::owner(), |a, b| a.cmp(b));
+    assert!(result.is_err());
+    assert_eq!(unsorted, tasks());
+}
+
+#[test]
+fn test_cached_keypath_across_passes() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let tasks = tasks();
+    let cached = cache_keypath(&tasks, counted_estimate(&calls));
+
+    let sums = tasks.window_by_keypath(&cached, 2, |pair| pair[0] + pair[1]).unwrap();
+    assert_eq!(sums, vec![4, 3, 3, 6]);
+    let maxima = tasks.rolling_by_keypath(&cached, 3, |window| *window.iter().max().unwrap()).unwrap();
+    assert_eq!(maxima, vec![3, 2, 5]);
+
+    let rows: Vec<&Task> = tasks.iter().collect();
+    let owner = computed_keypath(|task: &Task| task.owner.clone());
+    let by_owner = group_by_keypath(&rows, owner, |owner| owner).unwrap();
+    let ann = fold_keypath(by_owner[&Some("ann".to_string())].clone(), &cached, 0, |acc, &estimate| acc + estimate).unwrap();
+    assert_eq!(ann, 4);
+
+    assert_eq!(calls.load(Ordering::SeqCst), 5);
+    assert_eq!(cached.cached_count(), 5);
+    assert_eq!(cached.get(4), Some(&5));
+    assert_eq!(cached.get(5), None);
+}
+
+#[test]
+fn test_cached_keypath_index_based_passes() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let tasks = tasks();
+    let cached = cache_keypath(&tasks, counted_estimate(&calls));
+
+    assert_eq!(cached.window(2, |pair| pair[0] + pair[1]).unwrap(), vec![4, 3, 3, 6]);
+    assert!(cached.window(6, |pair| pair.len()).is_err());
+
+    let by_owner = cached.group_indices(computed_keypath(|task: &Task| task.owner.clone()), |owner| owner).unwrap();
+    assert_eq!(by_owner[&Some("bob".to_string())], vec![1, 4]);
+    let bob = cached.fold_indices(by_owner[&Some("bob".to_string())].iter().copied(), 0, |acc, &estimate| acc + estimate).unwrap();
+    assert_eq!(bob, 6);
+    assert_eq!(calls.load(Ordering::SeqCst), 5);
+
+    assert_eq!(cached.try_get(2).unwrap(), &2);
+    assert!(matches!(cached.try_get(5), Err(KeyPathError::InvalidAccess { .. })));
+}
+
+#[test]
+fn test_cached_keypath_rejects_foreign_roots() {
+    let tasks = tasks();
+    let cached = cache_keypath(&tasks, Task::title());
+    assert_eq!(cached.access(&tasks[2]).map(String::as_str), Some("review"));
+
+    let copy = tasks[2].clone();
+    assert!(cached.access(&copy).is_none());
+    assert!(matches!(cached.try_access(&copy), Err(KeyPathError::InvalidAccess { .. })));
+    assert_eq!(cached.cached_count(), 1);
+
+    // A cloned element is rejected by operations that skip absent values, not skipped
+    let clones = tasks.clone();
+    let folded = clones.iter().fold_keypath(&cached, 0, |acc, title| acc + title.len());
+    assert!(matches!(folded, Err(KeyPathError::InvalidAccess { .. })));
+    let collected = clones.into_iter().collect_keypath(&cached);
+    assert!(matches!(collected, Err(KeyPathError::InvalidAccess { .. })));
+
+    // The collection's own elements still read fine through references
+    let lengths = tasks.iter().fold_keypath(&cached, 0, |acc, title| acc + title.len()).unwrap();
+    assert_eq!(lengths, 38);
+}
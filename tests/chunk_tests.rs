@@ -0,0 +1,73 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+use std::rc::Rc;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Row {
+    customer_id: u32,
+    amount: u32,
+}
+
+fn rows(ids: &[u32]) -> Vec<Row> {
+    ids.iter()
+        .enumerate()
+        .map(|(index, &customer_id)| Row { customer_id, amount: index as u32 })
+        .collect()
+}
+
+fn ids(batches: &[Vec<Row>]) -> Vec<Vec<u32>> {
+    batches
+        .iter()
+        .map(|batch| batch.iter().map(|row| row.customer_id).collect())
+        .collect()
+}
+
+#[test]
+fn test_chunk_by_keypath_splits_runs() {
+    let chunks = chunk_by_keypath(rows(&[1, 1, 2, 2, 2, 1, 3]), Row::customer_id()).unwrap();
+    assert_eq!(ids(&chunks), vec![vec![1, 1], vec![2, 2, 2], vec![1], vec![3]]);
+    assert!(chunk_by_keypath(Vec::<Row>::new(), Row::customer_id()).unwrap().is_empty());
+
+    let shared: Vec<Rc<Row>> = rows(&[4, 4, 5]).into_iter().map(Rc::new).collect();
+    let chunks = chunk_by_keypath(shared, Row::customer_id()).unwrap();
+    assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+}
+
+#[test]
+fn test_batched_by_keypath_keeps_groups_together() {
+    let batches = batched_by_keypath(rows(&[1, 2, 1, 3, 3, 4, 2]), Row::customer_id(), 4).unwrap();
+    assert_eq!(ids(&batches), vec![vec![1, 1, 2, 2], vec![3, 3, 4]]);
+    // Elements keep their relative order within a group
+    assert_eq!(batches[0][0].amount, 0);
+    assert_eq!(batches[0][1].amount, 2);
+
+    // A group larger than the limit gets a batch of its own
+    let batches = batched_by_keypath(rows(&[1, 2, 2, 2, 3]), Row::customer_id(), 2).unwrap();
+    assert_eq!(ids(&batches), vec![vec![1], vec![2, 2, 2], vec![3]]);
+
+    let error = batched_by_keypath(rows(&[1]), Row::customer_id(), 0).unwrap_err();
+    assert!(matches!(error, KeyPathError::CollectionError { .. }));
+}
+
+#[test]
+fn test_chain_batching() {
+    let batches: Vec<Vec<Row>> = chain_keypath_ops(rows(&[1, 2, 3, 4, 5])).batched(2).collect();
+    assert_eq!(ids(&batches), vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+    let runs: Vec<Vec<Row>> = chain_keypath_ops(rows(&[7, 7, 8, 9, 9]))
+        .filter_by_keypath(Row::amount(), |&amount| amount != 2)
+        .chunk_by_keypath(Row::customer_id())
+        .collect();
+    assert_eq!(ids(&runs), vec![vec![7, 7], vec![9, 9]]);
+
+    let groups: Vec<Vec<Row>> = chain_keypath_ops(rows(&[1, 2, 1, 2, 3]))
+        .batched_by_keypath(Row::customer_id(), 3)
+        .collect();
+    assert_eq!(ids(&groups), vec![vec![1, 1], vec![2, 2, 3]]);
+}
+
+#[test]
+#[should_panic(expected = "Batch size must be greater than 0")]
+fn test_batched_rejects_zero() {
+    let _ = chain_keypath_ops(rows(&[1])).batched(0);
+}
@@ -0,0 +1,114 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::{Keypath, WritableKeypaths};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+#[derive(Keypath, WritableKeypaths, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Item {
+    name: String,
+    quantity: u32,
+}
+
+fn item(name: &str, quantity: u32) -> Item {
+    Item { name: name.to_string(), quantity }
+}
+
+fn sample_items() -> Vec<Item> {
+    vec![item("bolt", 30), item("nut", 5), item("gear", 12)]
+}
+
+#[test]
+fn test_read_only_methods_on_slices_and_arrays() {
+    let items = sample_items();
+    let slice: &[Item] = &items[1..];
+    assert_eq!(slice.collect_keypath(Item::quantity()).unwrap(), vec![5, 12]);
+    assert_eq!(slice.find_by_keypath(Item::quantity(), |&q| q > 10).unwrap(), Some(&items[2]));
+
+    let array = [item("a", 1), item("b", 2), item("c", 3)];
+    assert_eq!(array.count_by_keypath(Item::quantity(), |&q| q >= 2).unwrap(), 2);
+    assert_eq!(array.window_by_keypath(Item::quantity(), 2, |w| w[0] + w[1]).unwrap(), vec![3, 5]);
+
+    let boxed: Box<[Item]> = items.clone().into_boxed_slice();
+    assert!(boxed.all_by_keypath(Item::quantity(), |&q| q > 0).unwrap());
+}
+
+#[test]
+fn test_read_only_methods_on_deques_and_sets() {
+    let deque: VecDeque<Item> = sample_items().into_iter().collect();
+    let (large, small) = deque.partition_by_keypath(Item::quantity(), |&q| q >= 12).unwrap();
+    assert_eq!(large.len(), 2);
+    assert_eq!(small, vec![item("nut", 5)]);
+    assert_eq!(
+        deque.zip_with_keypath(&sample_items()[..2], Item::quantity(), Item::quantity(), |a, b| a + b).unwrap(),
+        vec![60, 10]
+    );
+
+    let set: HashSet<Item> = sample_items().into_iter().collect();
+    assert!(set.any_by_keypath(Item::name(), |name| name == "gear").unwrap());
+    assert_eq!(set.unique_by_keypath(Item::quantity()).unwrap().len(), 3);
+
+    let ordered: BTreeSet<Item> = sample_items().into_iter().collect();
+    assert_eq!(
+        ordered.collect_keypath(Item::name()).unwrap(),
+        vec!["bolt".to_string(), "gear".to_string(), "nut".to_string()]
+    );
+    assert_eq!(ordered.rolling_by_keypath(Item::quantity(), 2, |w| w[1]).unwrap(), vec![12, 5]);
+}
+
+#[test]
+fn test_sort_on_slices_and_deques() {
+    let mut items = sample_items();
+    items[..2].sort_by_keypath(Item::quantity(), |a, b| a.cmp(b)).unwrap();
+    assert_eq!(items.collect_keypath(Item::quantity()).unwrap(), vec![5, 30, 12]);
+
+    let mut deque: VecDeque<Item> = VecDeque::new();
+    deque.push_back(item("bolt", 30));
+    deque.push_front(item("nut", 5));
+    deque.push_front(item("gear", 12));
+    deque.sort_by_keypath(Item::name(), |a, b| a.cmp(b)).unwrap();
+    assert_eq!(deque.collect_keypath(Item::quantity()).unwrap(), vec![30, 12, 5]);
+}
+
+#[test]
+fn test_mutation_on_arrays_and_deques() {
+    let mut array = [item("a", 1), item("b", 2)];
+    array.for_each_keypath_mut(Item::quantity_w(), |q| *q *= 10).unwrap();
+    assert_eq!(array.collect_keypath(Item::quantity()).unwrap(), vec![10, 20]);
+
+    let mut deque: VecDeque<Item> = sample_items().into_iter().collect();
+    let updated = deque
+        .update_where(Item::quantity(), |&q| q < 10, Item::quantity_w(), |q| *q += 100)
+        .unwrap();
+    assert_eq!(updated, 1);
+    assert_eq!(deque[1], item("nut", 105));
+}
+
+#[test]
+fn test_dedup_keeps_elements_in_order() {
+    let items = vec![item("bolt", 5), item("nut", 3), item("bolt", 7), item("gear", 3)];
+
+    let first = items.dedup_by_keypath(Item::name(), KeepOccurrence::First).unwrap();
+    assert_eq!(first, vec![item("bolt", 5), item("nut", 3), item("gear", 3)]);
+
+    let last = items.dedup_by_keypath(Item::name(), KeepOccurrence::Last).unwrap();
+    assert_eq!(last, vec![item("nut", 3), item("bolt", 7), item("gear", 3)]);
+
+    let by_quantity = dedup_by_keypath(items, Item::quantity(), KeepOccurrence::Last).unwrap();
+    assert_eq!(by_quantity, vec![item("bolt", 5), item("bolt", 7), item("gear", 3)]);
+}
+
+#[test]
+fn test_duplicates_and_uniqueness() {
+    let items = vec![item("bolt", 5), item("nut", 3), item("bolt", 7), item("gear", 3), item("bolt", 1)];
+
+    let duplicates = items.duplicates_by_keypath(Item::name()).unwrap();
+    assert_eq!(
+        duplicates,
+        vec![("bolt".to_string(), vec![item("bolt", 5), item("bolt", 7), item("bolt", 1)])]
+    );
+    let by_quantity = items.duplicates_by_keypath(Item::quantity()).unwrap();
+    assert_eq!(by_quantity, vec![(3, vec![item("nut", 3), item("gear", 3)])]);
+
+    assert!(!items.is_unique_by_keypath(Item::name()).unwrap());
+    assert!(sample_items().is_unique_by_keypath(Item::name()).unwrap());
+    assert!(Vec::<Item>::new().is_unique_by_keypath(Item::quantity()).unwrap());
+}
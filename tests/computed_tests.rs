@@ -0,0 +1,142 @@
+use key_paths_derive::Keypath;
+use rust_prelude_plus::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Keypath, Debug, Clone, PartialEq, Eq, Hash)]
+struct Person {
+    id: u32,
+    first: String,
+    last: String,
+    age: u32,
+}
+
+fn person(id: u32, first: &str, last: &str, age: u32) -> Person {
+    Person {
+        id,
+        first: first.to_string(),
+        last: last.to_string(),
+        age,
+    }
+}
+
+fn people() -> Vec<Person> {
+    vec![
+        person(1, "Ada", "Lovelace", 36),
+        person(2, "Alan", "Turing", 41),
+        person(3, "Grace", "Hopper", 85),
+        person(4, "Alan", "Kay", 84),
+    ]
+}
+
+fn full_name() -> ComputedKeyPath<Person, String> {
+    computed_keypath(|person: &Person| format!("{} {}", person.first, person.last))
+}
+
+#[test]
+fn test_computed_values_in_operations() {
+    let people = people();
+    let decade = computed_keypath(|person: &Person| person.age / 10 * 10);
+
+    let names = map_keypath_collection(&people, full_name(), |name| name).unwrap();
+    assert_eq!(names, vec!["Ada Lovelace", "Alan Turing", "Grace Hopper", "Alan Kay"]);
+
+    let long_names = people.count_by_keypath(full_name(), |name| name.len() > 11).unwrap();
+    assert_eq!(long_names, 2);
+
+    let by_decade = group_by_keypath(&people, decade.clone(), |decade| decade).unwrap();
+    assert_eq!(by_decade[&80].len(), 2);
+
+    let counts: HashMap<u32, usize> = people.value_counts_by_keypath(decade).unwrap().into_iter().collect();
+    assert_eq!(counts[&30], 1);
+
+    let mut sorted = people.clone();
+    sort_by_keypath(&mut sorted, full_name(), |a, b| a.cmp(&b)).unwrap();
+    assert_eq!(sorted[0].id, 1);
+    assert_eq!(sorted[1].id, 4);
+    assert!(sorted.is_sorted_by_keypath(full_name(), |a, b| a.cmp(&b)).unwrap());
+
+    let index = sorted.binary_search_by_keypath(full_name(), &"Grace Hopper".to_string(), |name, target| name.cmp(target)).unwrap();
+    assert_eq!(index, Ok(3));
+}
+
+#[test]
+fn test_memoized_computation_runs_once_per_key() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let score = computed_keypath(move |person: &Person| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        person.age * 2
+    })
+    .memoized_by(16, |person: &Person| person.id);
+    assert!(score.is_memoized());
+
+    let mut people = people();
+    people.extend(people.clone());
+    let total = fold_keypath(people.clone(), score.clone(), 0, |acc, score| acc + score).unwrap();
+    assert_eq!(total, 2 * 2 * (36 + 41 + 85 + 84));
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+    // Sorting moves elements around; cached values follow the keys, not their positions
+    sort_by_keypath(&mut people, score.clone(), |a, b| b.cmp(&a)).unwrap();
+    assert_eq!(people[0].first, "Grace");
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    assert_eq!(score.cached_count(), 4);
+}
+
+#[test]
+fn test_memoized_cache_is_bounded_and_clearable() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let initials = computed_keypath(move |person: &Person| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        format!("{}{}", &person.first[..1], &person.last[..1])
+    })
+    .memoized_by(3, |person: &Person| person.id);
+
+    let people = people();
+    let first = collect_keypath(people.clone(), initials.clone()).unwrap();
+    assert_eq!(first, vec!["AL", "AT", "GH", "AK"]);
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    assert!(initials.cached_count() <= 3);
+
+    // Clones share the cache, so clearing one clears them all
+    let copy = initials.clone();
+    copy.clear_cache();
+    assert_eq!(initials.cached_count(), 0);
+    let second = collect_keypath(people, initials.clone()).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(calls.load(Ordering::SeqCst), 8);
+
+    let uncached = computed_keypath(|person: &Person| person.age).memoized_by(0, |person: &Person| person.id);
+    assert_eq!(collect_keypath(self::people(), uncached.clone()).unwrap(), vec![36, 41, 85, 84]);
+    assert_eq!(uncached.cached_count(), 0);
+}
+
+#[test]
+fn test_computed_zipped_with_field() {
+    let people = people();
+    let name_age = full_name().zip(Person::age());
+
+    let found = find_by_keypath(people.clone(), name_age.clone(), |(name, &age)| name.starts_with("Alan") && age > 50).unwrap();
+    assert_eq!(found.unwrap().id, 4);
+
+    let pairs = collect_keypath(people[..1].to_vec(), name_age).unwrap();
+    assert_eq!(pairs, vec![("Ada Lovelace".to_string(), 36)]);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_computed_values() {
+    use rust_prelude_plus::parallel::parallel_collections::{par_filter_by_keypath, par_map_keypath, par_top_k_by_keypath};
+
+    let lengths = par_map_keypath(people(), full_name(), |name| name.len()).unwrap();
+    assert_eq!(lengths, vec![12, 11, 12, 8]);
+
+    let seniors = par_filter_by_keypath(people(), computed_keypath(|person: &Person| person.age >= 80).memoized_by(8, |person: &Person| person.id), |senior| senior).unwrap();
+    assert_eq!(seniors.len(), 2);
+
+    let longest = par_top_k_by_keypath(people(), full_name(), 1, |a, b| a.len().cmp(&b.len()).then(b.cmp(a))).unwrap();
+    assert_eq!(longest[0].id, 1);
+}
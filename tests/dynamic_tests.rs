@@ -0,0 +1,112 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+use key_paths_core::KeyPaths;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Product {
+    name: String,
+    price: f64,
+    stock: u32,
+    tags: Vec<String>,
+}
+
+fn sample_products() -> Vec<Product> {
+    vec![
+        Product { name: "Laptop".to_string(), price: 999.99, stock: 5, tags: vec!["tech".to_string()] },
+        Product { name: "Book".to_string(), price: 19.99, stock: 42, tags: vec!["paper".to_string()] },
+    ]
+}
+
+fn registry() -> KeyPathRegistry<Product> {
+    KeyPathRegistry::new()
+        .register("name", Product::name())
+        .register("price", Product::price())
+        .register("stock", Product::stock())
+}
+
+#[test]
+fn test_any_keypath_formats_and_downcasts() {
+    let products = sample_products();
+    let price = AnyKeyPath::new(Product::price());
+
+    assert!(price.is::<f64>());
+    assert_eq!(price.value_type_name(), "f64");
+
+    let value = price.get(&products[1]).unwrap();
+    assert_eq!(value.to_string(), "19.99");
+    assert_eq!(format!("{:?}", value), "19.99");
+    assert_eq!(value.downcast_ref::<f64>(), Some(&19.99));
+    assert_eq!(value.downcast_ref::<u32>(), None);
+    assert_eq!(price.get_as::<f64>(&products[0]), Some(&999.99));
+}
+
+#[test]
+fn test_any_keypath_from_debug() {
+    let products = sample_products();
+    let tags = AnyKeyPath::from_debug(KeyPaths::readable(|p: &Product| &p.tags));
+
+    let values = collect_any_keypath(&products, &tags).unwrap();
+    let rendered: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    assert_eq!(rendered, vec!["[\"tech\"]", "[\"paper\"]"]);
+}
+
+#[test]
+fn test_registry_lookup_and_order() {
+    let registry = registry();
+    assert_eq!(registry.len(), 3);
+    assert_eq!(registry.names().collect::<Vec<_>>(), vec!["name", "price", "stock"]);
+    assert!(registry.get("stock").unwrap().is::<u32>());
+    assert!(registry.get("missing").is_none());
+
+    let err = registry.require("missing").unwrap_err();
+    assert_eq!(
+        err,
+        KeyPathError::InvalidAccess {
+            message: "unknown field 'missing' (known fields: name, price, stock)".to_string()
+        }
+    );
+
+    let products = sample_products();
+    let stock = registry.get_value(&products[1], "stock").unwrap();
+    assert_eq!(stock.downcast_ref::<u32>(), Some(&42));
+}
+
+#[test]
+fn test_registry_replaces_existing_name() {
+    let mut registry = registry();
+    let previous = registry.insert("price", AnyKeyPath::new(Product::stock()));
+    assert!(previous.unwrap().is::<f64>());
+    assert_eq!(registry.names().collect::<Vec<_>>(), vec!["name", "price", "stock"]);
+    assert!(registry.get("price").unwrap().is::<u32>());
+}
+
+#[test]
+fn test_select_by_names() {
+    let products = sample_products();
+    let registry = registry();
+
+    let rows = select_by_names(&products, &registry, &["stock", "name"]).unwrap();
+    let rendered: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|value| value.to_string()).collect())
+        .collect();
+    assert_eq!(rendered, vec![vec!["5", "Laptop"], vec!["42", "Book"]]);
+
+    assert!(select_by_names(&products, &registry, &["name", "weight"]).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serializable_values() {
+    let products = sample_products();
+    let registry = KeyPathRegistry::new()
+        .register_any("name", AnyKeyPath::serializable(Product::name()))
+        .register_any("stock", AnyKeyPath::serializable(Product::stock()))
+        .register("price", Product::price());
+
+    let rows = select_by_names(&products, &registry, &["name", "stock"]).unwrap();
+    assert_eq!(serde_json::to_string(&rows).unwrap(), r#"[["Laptop",5],["Book",42]]"#);
+
+    let price = registry.get_value(&products[0], "price").unwrap();
+    assert!(serde_json::to_string(&price).is_err());
+}
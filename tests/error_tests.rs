@@ -0,0 +1,109 @@
+use rust_prelude_plus::prelude::*;
+use std::error::Error;
+use std::io;
+
+#[derive(Debug)]
+struct Outer(io::Error);
+
+impl std::fmt::Display for Outer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "loading config")
+    }
+}
+
+impl Error for Outer {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn load() -> KeyPathResult<String> {
+    Err(io::Error::new(io::ErrorKind::PermissionDenied, "config.toml"))?
+}
+
+#[test]
+fn test_codes_are_stable() {
+    let errors = [
+        (KeyPathError::InvalidAccess { message: String::new() }, "invalid_access"),
+        (KeyPathError::CollectionError { message: String::new() }, "collection_error"),
+        (KeyPathError::ClosureError { index: 0, message: String::new(), source: ErrorSource::new(io::Error::other("parse")) }, "closure_error"),
+        (KeyPathError::Multiple { errors: vec![] }, "multiple"),
+        (KeyPathError::from(io::Error::other("disk")), "io_error"),
+    ];
+    for (error, code) in errors {
+        assert_eq!(error.code(), code);
+    }
+}
+
+#[test]
+fn test_sources_survive_conversion_and_clone() {
+    let error = load().unwrap_err();
+    assert_eq!(error.to_string(), "I/O error: config.toml");
+    let cloned = error.clone();
+    assert_eq!(cloned, error);
+
+    // source() is the original error itself, not a wrapper around it
+    let source = cloned.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::PermissionDenied);
+
+    // The whole cause chain is reachable, as anyhow and eyre walk it
+    let wrapped = KeyPathError::runtime_with_source(Outer(io::Error::other("missing")));
+    assert_eq!(wrapped.code(), "runtime_failure");
+    assert_eq!(wrapped.to_string(), "Runtime failure: loading config");
+    assert!(matches!(wrapped.kind(), KeyPathError::RuntimeFailure { .. }));
+    let mut chain = Vec::new();
+    let mut cause = wrapped.source();
+    while let Some(error) = cause {
+        chain.push(error.to_string());
+        cause = error.source();
+    }
+    assert_eq!(chain, vec!["loading config", "missing"]);
+
+    // Sources are equal only to their own clones, never to another error with the same message
+    assert_ne!(KeyPathError::from(io::Error::other("disk")), KeyPathError::from(io::Error::other("disk")));
+    assert_eq!(wrapped.clone(), wrapped);
+
+    // Converting keeps only the message, so the variant shapes stay unchanged
+    let converted = Outer(io::Error::other("missing")).into_keypath_error();
+    assert_eq!(converted, KeyPathError::RuntimeFailure { message: "loading config".to_string() });
+    assert!(converted.source().is_none());
+}
+
+#[test]
+fn test_usable_as_boxed_error() {
+    fn assert_send_sync<E: Error + Send + Sync + 'static>() {}
+    assert_send_sync::<KeyPathError>();
+
+    fn run() -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(load()?)
+    }
+    let boxed = run().unwrap_err();
+    assert_eq!(boxed.downcast_ref::<KeyPathError>().unwrap().code(), "io_error");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_errors_convert() {
+    use serde::de::Error as _;
+
+    let error = KeyPathError::custom("missing field `age`");
+    assert_eq!(error.code(), "serialization_error");
+    assert!(error.source().is_none());
+
+    let json = serde_json::from_str::<u32>("\"x\"").unwrap_err();
+    let error = KeyPathError::serialization(json);
+    assert!(error.to_string().starts_with("Serialization error: invalid type"));
+    assert!(error.source().is_some());
+
+    let value: KeyPathError = serde::de::value::Error::custom("bad value").into();
+    assert_eq!(value.to_string(), "Serialization error: bad value");
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_rayon_pool_errors_convert() {
+    rayon::ThreadPoolBuilder::new().build_global().ok();
+    let error: KeyPathError = rayon::ThreadPoolBuilder::new().build_global().unwrap_err().into();
+    assert_eq!(error.code(), "parallel_error");
+    assert!(error.source().is_some());
+}
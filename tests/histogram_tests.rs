@@ -0,0 +1,125 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Review {
+    product: String,
+    rating: u32,
+    price: f64,
+}
+
+fn review(product: &str, rating: u32, price: f64) -> Review {
+    Review { product: product.to_string(), rating, price }
+}
+
+fn reviews() -> Vec<Review> {
+    vec![
+        review("lamp", 5, 10.0),
+        review("desk", 3, 20.0),
+        review("lamp", 4, 35.0),
+        review("chair", 5, 40.0),
+        review("desk", 1, 50.0),
+        review("lamp", 5, 100.0),
+    ]
+}
+
+fn counts(buckets: &[HistogramBucket]) -> Vec<usize> {
+    buckets.iter().map(|bucket| bucket.count).collect()
+}
+
+#[test]
+fn test_equal_width_histogram() {
+    let buckets = reviews().histogram_by_keypath(Review::price(), Binning::EqualWidth(3)).unwrap();
+    assert_eq!(buckets.len(), 3);
+    assert_eq!((buckets[0].lower, buckets[0].upper), (10.0, 40.0));
+    assert_eq!((buckets[2].lower, buckets[2].upper), (70.0, 100.0));
+    // 40.0 starts the second bucket and 100.0 is included in the last one
+    assert_eq!(counts(&buckets), vec![3, 2, 1]);
+
+    let single = vec![review("lamp", 5, 7.0)];
+    let buckets = single.histogram_by_keypath(Review::price(), Binning::EqualWidth(2)).unwrap();
+    assert_eq!(counts(&buckets), vec![0, 1]);
+    assert!(Vec::<Review>::new().histogram_by_keypath(Review::price(), Binning::EqualWidth(2)).unwrap().is_empty());
+}
+
+#[test]
+fn test_edges_histogram_with_elements() {
+    let reviews = reviews();
+    let buckets = reviews
+        .histogram_elements_by_keypath(Review::rating(), Binning::Edges(vec![2.0, 4.0, 5.0]))
+        .unwrap();
+    assert_eq!(buckets.len(), 2);
+    assert_eq!(buckets[0].0, HistogramBucket { lower: 2.0, upper: 4.0, count: 1 });
+    assert_eq!(buckets[0].1, vec![&reviews[1]]);
+    // The rating of 1 falls below the first edge and is not counted
+    assert_eq!(buckets[1].0.count, 4);
+
+    let error = reviews.histogram_by_keypath(Review::rating(), Binning::Edges(vec![3.0, 3.0])).unwrap_err();
+    assert!(matches!(error, KeyPathError::CollectionError { .. }));
+    assert!(reviews.histogram_by_keypath(Review::rating(), Binning::EqualWidth(0)).is_err());
+}
+
+#[test]
+fn test_quantile_histogram() {
+    let buckets = reviews().histogram_by_keypath(Review::price(), Binning::Quantiles(2)).unwrap();
+    assert_eq!(buckets.len(), 2);
+    assert_eq!((buckets[0].lower, buckets[0].upper, buckets[1].upper), (10.0, 37.5, 100.0));
+    assert_eq!(counts(&buckets), vec![3, 3]);
+
+    // Repeated ratings collapse coinciding edges
+    let buckets = reviews().histogram_by_keypath(Review::rating(), Binning::Quantiles(4)).unwrap();
+    assert_eq!(counts(&buckets).iter().sum::<usize>(), 6);
+    assert!(buckets.windows(2).all(|pair| pair[0].upper == pair[1].lower && pair[0].lower < pair[0].upper));
+}
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Sample {
+    score: f64,
+    bytes: u64,
+    delta: i64,
+}
+
+#[test]
+fn test_histogram_ignores_non_finite_values() {
+    let samples: Vec<Sample> = [0.0, f64::INFINITY, 4.0, f64::NEG_INFINITY, f64::NAN]
+        .into_iter()
+        .map(|score| Sample { score, bytes: 0, delta: 0 })
+        .collect();
+
+    let buckets = samples.histogram_by_keypath(Sample::score(), Binning::EqualWidth(2)).unwrap();
+    assert_eq!((buckets[0].lower, buckets[1].upper), (0.0, 4.0));
+    assert_eq!(counts(&buckets), vec![1, 1]);
+
+    let buckets = samples.histogram_by_keypath(Sample::score(), Binning::Quantiles(2)).unwrap();
+    assert_eq!(counts(&buckets), vec![1, 1]);
+
+    let only_infinite = vec![Sample { score: f64::INFINITY, bytes: 0, delta: 0 }];
+    assert!(only_infinite.histogram_by_keypath(Sample::score(), Binning::EqualWidth(2)).unwrap().is_empty());
+}
+
+#[test]
+fn test_histogram_of_wide_integers() {
+    let samples: Vec<Sample> = [(10u64, -5i64), (20, 5), (u64::MAX, 15)]
+        .into_iter()
+        .map(|(bytes, delta)| Sample { score: 0.0, bytes, delta })
+        .collect();
+
+    let buckets = samples.histogram_by_keypath(Sample::bytes(), Binning::Edges(vec![0.0, 100.0, f64::INFINITY])).unwrap();
+    assert_eq!(counts(&buckets), vec![2, 1]);
+
+    let buckets = samples.histogram_by_keypath(Sample::delta(), Binning::EqualWidth(2)).unwrap();
+    assert_eq!((buckets[0].lower, buckets[1].upper), (-5.0, 15.0));
+    assert_eq!(counts(&buckets), vec![1, 2]);
+}
+
+#[test]
+fn test_value_counts_sorted_by_frequency() {
+    let counts = reviews().value_counts_by_keypath(Review::product()).unwrap();
+    assert_eq!(
+        counts,
+        vec![("lamp".to_string(), 3), ("desk".to_string(), 2), ("chair".to_string(), 1)]
+    );
+
+    let ratings = reviews().value_counts_by_keypath(Review::rating()).unwrap();
+    assert_eq!(ratings, vec![(5, 3), (3, 1), (4, 1), (1, 1)]);
+}
@@ -0,0 +1,103 @@
+use rust_prelude_plus::prelude::*;
+use rust_prelude_plus::collections::specialized::{KeyPathsBTreeMapExt, KeyPathsHashMapExt};
+use key_paths_derive::Keypath;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Account {
+    owner: String,
+    region: String,
+    balance: u64,
+}
+
+fn account(owner: &str, region: &str, balance: u64) -> Account {
+    Account { owner: owner.to_string(), region: region.to_string(), balance }
+}
+
+fn accounts_by_id() -> BTreeMap<u32, Account> {
+    BTreeMap::from([
+        (1, account("alice", "eu", 500)),
+        (2, account("bob", "us", 120)),
+        (3, account("carol", "eu", 80)),
+        (4, account("dave", "us", 900)),
+    ])
+}
+
+fn hash_accounts() -> HashMap<u32, Account> {
+    accounts_by_id().into_iter().collect()
+}
+
+#[test]
+fn test_hashmap_group_fold_find() {
+    let accounts = hash_accounts();
+
+    let groups = accounts.group_values_by_keypath(Account::region()).unwrap();
+    assert_eq!(groups["eu"].len(), 2);
+    assert!(groups["us"].contains_key(&4));
+
+    let total = accounts.fold_values_keypath(Account::balance(), 0, |acc, &balance| acc + balance).unwrap();
+    assert_eq!(total, 1600);
+
+    let (id, found) = accounts.find_value_by_keypath(Account::owner(), |owner| owner == "carol").unwrap().unwrap();
+    assert_eq!((*id, found.balance), (3, 80));
+    assert!(accounts.find_value_by_keypath(Account::balance(), |&b| b > 1000).unwrap().is_none());
+}
+
+#[test]
+fn test_hashmap_sort_index_retain() {
+    let mut accounts = hash_accounts();
+
+    let sorted = accounts.sort_entries_by_keypath(Account::balance(), |a, b| b.cmp(a)).unwrap();
+    let ids: Vec<u32> = sorted.iter().map(|(id, _)| **id).collect();
+    assert_eq!(ids, vec![4, 1, 2, 3]);
+
+    let by_owner = accounts.index_by_keypath(Account::owner()).unwrap();
+    assert_eq!(by_owner["dave"].balance, 900);
+    assert!(matches!(
+        accounts.index_by_keypath(Account::region()),
+        Err(KeyPathError::CollectionError { .. })
+    ));
+
+    accounts.retain_by_keypath(Account::balance(), |&b| b >= 120).unwrap();
+    let mut remaining: Vec<u32> = accounts.keys().copied().collect();
+    remaining.sort();
+    assert_eq!(remaining, vec![1, 2, 4]);
+}
+
+#[test]
+fn test_btreemap_ordered_operations() {
+    let mut accounts = accounts_by_id();
+
+    let groups = accounts.group_values_by_keypath(Account::region()).unwrap();
+    assert_eq!(groups["eu"].keys().copied().collect::<Vec<_>>(), vec![1, 3]);
+
+    let owners = accounts.fold_values_keypath(Account::owner(), String::new(), |acc, owner| acc + &owner[..1]).unwrap();
+    assert_eq!(owners, "abcd");
+
+    let (id, _) = accounts.find_value_by_keypath(Account::region(), |region| region == "us").unwrap().unwrap();
+    assert_eq!(*id, 2);
+
+    let sorted = accounts.sort_entries_by_keypath(Account::region(), |a, b| a.cmp(b)).unwrap();
+    let ids: Vec<u32> = sorted.iter().map(|(id, _)| **id).collect();
+    assert_eq!(ids, vec![1, 3, 2, 4]);
+
+    let by_balance = accounts.index_by_keypath(Account::balance()).unwrap();
+    assert_eq!(by_balance.keys().copied().collect::<Vec<_>>(), vec![80, 120, 500, 900]);
+
+    accounts.retain_by_keypath(Account::region(), |region| region == "eu").unwrap();
+    assert_eq!(accounts.keys().copied().collect::<Vec<_>>(), vec![1, 3]);
+}
+
+#[test]
+fn test_btreemap_range_queries() {
+    let accounts = accounts_by_id();
+
+    let rich_in_range = accounts.range_by_keypath(2..=4, Account::balance(), |&b| b > 100).unwrap();
+    let ids: Vec<u32> = rich_in_range.iter().map(|(id, _)| **id).collect();
+    assert_eq!(ids, vec![2, 4]);
+
+    let eu_from_2 = accounts.filter_range_keypath(2.., Account::region(), |region| region == "eu").unwrap();
+    assert_eq!(eu_from_2, BTreeMap::from([(3, account("carol", "eu", 80))]));
+
+    assert!(accounts.range_by_keypath(..1, Account::balance(), |_| true).unwrap().is_empty());
+}
@@ -0,0 +1,106 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::{Keypath, WritableKeypaths};
+
+#[derive(Keypath, WritableKeypaths, Debug, Clone, PartialEq)]
+struct Product {
+    name: String,
+    price: f64,
+    category: String,
+    stock: u32,
+}
+
+fn sample_products() -> Vec<Product> {
+    vec![
+        Product { name: "Laptop".to_string(), price: 1000.0, category: "Electronics".to_string(), stock: 5 },
+        Product { name: "Book".to_string(), price: 20.0, category: "Books".to_string(), stock: 10 },
+        Product { name: "Phone".to_string(), price: 500.0, category: "Electronics".to_string(), stock: 0 },
+    ]
+}
+
+#[test]
+fn test_for_each_keypath_mut() {
+    let mut products = sample_products();
+    for_each_keypath_mut(&mut products, Product::stock_w(), |stock| *stock += 1).unwrap();
+    assert_eq!(collect_keypath(products, Product::stock()).unwrap(), vec![6, 11, 1]);
+}
+
+#[test]
+fn test_for_each_keypath_mut_readable_keypath_fails() {
+    let mut products = sample_products();
+    let result = for_each_keypath_mut(&mut products, Product::stock(), |stock| *stock += 1);
+    assert!(matches!(result, Err(KeyPathError::InvalidAccess { .. })));
+}
+
+#[test]
+fn test_update_where() {
+    let mut products = sample_products();
+    let updated = update_where(
+        &mut products,
+        Product::category(),
+        |category| category == "Electronics",
+        Product::price_w(),
+        |price| *price *= 0.5,
+    ).unwrap();
+
+    assert_eq!(updated, 2);
+    assert_eq!(collect_keypath(products, Product::price()).unwrap(), vec![500.0, 20.0, 250.0]);
+}
+
+#[test]
+fn test_collection_ext_mutation() {
+    let mut products = sample_products();
+    products.for_each_keypath_mut(Product::name_w(), |name| name.make_ascii_uppercase()).unwrap();
+    let updated = products
+        .update_where(Product::stock(), |&stock| stock == 0, Product::stock_w(), |stock| *stock = 100)
+        .unwrap();
+
+    assert_eq!(updated, 1);
+    assert_eq!(products[0].name, "LAPTOP");
+    assert_eq!(products[2].stock, 100);
+}
+
+#[test]
+fn test_collection_ext_mutation_through_trait_path() {
+    let mut products = sample_products();
+    KeyPathsCollectionExt::sort_by_keypath(&mut products, Product::stock(), |a, b| a.cmp(b)).unwrap();
+    KeyPathsCollectionExt::for_each_keypath_mut(&mut products, Product::stock_w(), |stock| *stock += 1).unwrap();
+    assert_eq!(collect_keypath(products.clone(), Product::stock()).unwrap(), vec![1, 6, 11]);
+
+    let mut queue: std::collections::VecDeque<Product> = products.into_iter().collect();
+    queue.rotate_left(1);
+    let updated = KeyPathsCollectionExt::update_where(&mut queue, Product::stock(), |&stock| stock > 5, Product::price_w(), |price| *price = 0.0).unwrap();
+    assert_eq!(updated, 2);
+    assert_eq!(queue.iter().map(|product| product.price).collect::<Vec<_>>(), vec![0.0, 0.0, 500.0]);
+}
+
+#[derive(Keypath, WritableKeypaths, Debug, Clone, PartialEq)]
+struct Listing {
+    discount: Option<u32>,
+    price: f64,
+}
+
+#[test]
+fn test_update_where_missing_condition_value_is_an_error() {
+    let mut listings = vec![
+        Listing { discount: Some(10), price: 100.0 },
+        Listing { discount: None, price: 50.0 },
+    ];
+    let result = listings.update_where(Listing::discount(), |&discount| discount > 5, Listing::price_w(), |price| *price *= 0.9);
+    assert!(matches!(result, Err(KeyPathError::InvalidAccess { .. })));
+
+    let mut queue: std::collections::VecDeque<Listing> = listings.into_iter().rev().collect();
+    let result = queue.update_where(Listing::discount(), |&discount| discount > 5, Listing::price_w(), |price| *price *= 0.9);
+    assert!(matches!(result, Err(KeyPathError::InvalidAccess { .. })));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_par_for_each_keypath_mut() {
+    use rust_prelude_plus::parallel::parallel_collections::par_for_each_keypath_mut;
+
+    let mut products = sample_products();
+    par_for_each_keypath_mut(&mut products, Product::price_w(), |price| *price += 1.0).unwrap();
+    assert_eq!(products[0].price, 1001.0);
+    assert_eq!(products[1].price, 21.0);
+    assert_eq!(products[2].price, 501.0);
+}
@@ -0,0 +1,120 @@
+use key_paths_core::KeyPaths;
+use key_paths_derive::{Keypath, WritableKeypaths};
+use rust_prelude_plus::prelude::*;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    Click { x: i32, y: i32 },
+    Scroll(f64),
+    Key(char),
+}
+
+fn click_x() -> KeyPaths<Event, i32> {
+    // Reads a field of the variant, but has no way to build an `Event` from it
+    KeyPaths::failable_readable(|event: &Event| match event {
+        Event::Click { x, .. } => Some(x),
+        _ => None,
+    })
+}
+
+fn scroll() -> KeyPaths<Event, f64> {
+    KeyPaths::readable_enum(Event::Scroll, |event: &Event| match event {
+        Event::Scroll(delta) => Some(delta),
+        _ => None,
+    })
+}
+
+fn key() -> KeyPaths<Event, char> {
+    KeyPaths::writable_enum(
+        Event::Key,
+        |event: &Event| match event {
+            Event::Key(c) => Some(c),
+            _ => None,
+        },
+        |event: &mut Event| match event {
+            Event::Key(c) => Some(c),
+            _ => None,
+        },
+    )
+}
+
+fn events() -> Vec<Event> {
+    vec![
+        Event::Scroll(1.0),
+        Event::Key('a'),
+        Event::Click { x: 3, y: 4 },
+        Event::Scroll(-2.5),
+        Event::Key('b'),
+    ]
+}
+
+#[test]
+fn test_filter_and_extract_variants() {
+    let events = events();
+    assert_eq!(filter_variant(events.clone(), scroll()), vec![Event::Scroll(1.0), Event::Scroll(-2.5)]);
+    assert_eq!(extract_variant(&events, key()), vec![&'a', &'b']);
+
+    let shared: Vec<Rc<Event>> = events.into_iter().map(Rc::new).collect();
+    assert_eq!(filter_variant(shared.clone(), key()).len(), 2);
+    assert_eq!(extract_variant(&shared, scroll()), vec![&1.0, &-2.5]);
+}
+
+#[test]
+fn test_map_variant_rebuilds_or_updates_in_place() {
+    let scrolled = map_variant(events(), scroll(), |delta| delta.abs()).unwrap();
+    assert_eq!(scrolled[3], Event::Scroll(2.5));
+    assert_eq!(scrolled[2], Event::Click { x: 3, y: 4 });
+
+    let upper = map_variant(events(), key(), |c| c.to_ascii_uppercase()).unwrap();
+    assert_eq!(extract_variant(&upper, key()), vec![&'A', &'B']);
+    assert_eq!(upper[0], Event::Scroll(1.0));
+
+    assert_eq!(extract_variant(&events(), click_x()), vec![&3]);
+    let error = map_variant(events(), click_x(), |x| x + 1).unwrap_err();
+    assert_eq!(error.code(), "invalid_access");
+}
+
+#[derive(Keypath, WritableKeypaths, Debug, Clone, PartialEq)]
+struct LineItem {
+    name: String,
+    price_cents: u64,
+}
+
+fn dollars() -> Iso<u64, f64> {
+    Iso::new(|&cents: &u64| cents as f64 / 100.0, |&dollars: &f64| (dollars * 100.0).round() as u64)
+}
+
+#[test]
+fn test_iso_keypaths_read_and_write() {
+    let mut items = vec![
+        LineItem { name: "Pen".to_string(), price_cents: 150 },
+        LineItem { name: "Pad".to_string(), price_cents: 399 },
+    ];
+
+    let read = LineItem::price_cents().iso(dollars());
+    assert_eq!(read.get(&items[0]).unwrap(), 1.5);
+    assert_eq!(read.get_all(&items).unwrap(), vec![1.5, 3.99]);
+
+    let write = LineItem::price_cents_w().iso(dollars());
+    write.set(&mut items[0], 2.25).unwrap();
+    assert_eq!(items[0].price_cents, 225);
+    write.modify_all(&mut items, |price| price * 2.0).unwrap();
+    assert_eq!(items.iter().map(|item| item.price_cents).collect::<Vec<_>>(), vec![450, 798]);
+
+    // Readable-only keypaths cannot be written through
+    assert!(read.set(&mut items[0], 1.0).is_err());
+    assert!(write.get(&items[0]).is_err());
+}
+
+#[test]
+fn test_iso_composition_round_trips() {
+    let thousands = Iso::new(|&dollars: &f64| dollars / 1000.0, |&thousands: &f64| thousands * 1000.0);
+    let cents_to_thousands = dollars().then(thousands);
+    assert_eq!(cents_to_thousands.get(&250_000), 2.5);
+    assert_eq!(cents_to_thousands.reverse_get(&2.5), 250_000);
+
+    let cents = dollars().reverse();
+    assert_eq!(cents.get(&12.34), 1234);
+    assert_eq!(cents.reverse_get(&1234), 12.34);
+}
@@ -0,0 +1,135 @@
+use key_paths_derive::Keypath;
+use rust_prelude_plus::{keypath_pipeline, keypath_pipeline_op};
+use std::rc::Rc;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Employee {
+    id: u32,
+    name: String,
+    department: String,
+    salary: f64,
+    manager: Option<u32>,
+}
+
+#[derive(Keypath, Debug, Clone)]
+struct Project {
+    owner: u32,
+    title: String,
+}
+
+fn employee(id: u32, name: &str, department: &str, salary: f64, manager: Option<u32>) -> Employee {
+    Employee {
+        id,
+        name: name.to_string(),
+        department: department.to_string(),
+        salary,
+        manager,
+    }
+}
+
+fn employees() -> Vec<Employee> {
+    vec![
+        employee(1, "Alice", "Engineering", 120.0, None),
+        employee(2, "Bob", "Engineering", 95.0, Some(1)),
+        employee(3, "Carol", "Sales", 80.0, Some(1)),
+        employee(4, "Dave", "Sales", 70.0, Some(3)),
+        employee(5, "Eve", "Engineering", 105.0, Some(1)),
+    ]
+}
+
+#[test]
+fn test_stages_change_element_type() {
+    let names = keypath_pipeline!(employees() =>
+        filter(Employee::department(), |department| department == "Engineering"),
+        sort(Employee::salary(), |a, b| b.partial_cmp(a).unwrap()),
+        skip(1),
+        map(Employee::name(), |name| name.to_uppercase()),
+        map(|name| name.len()),
+        take(5),
+    );
+    assert_eq!(names, vec![3, 3]);
+
+    let sorted = keypath_pipeline!(employees() => sort(Employee::name()), map(Employee::id(), |&id| id));
+    assert_eq!(sorted, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_terminal_group_and_fold() {
+    let by_department = keypath_pipeline!(employees() =>
+        filter(|employee: &Employee| employee.salary > 75.0),
+        group(Employee::department()),
+    );
+    assert_eq!(by_department["Engineering"].len(), 3);
+    assert_eq!(by_department["Sales"].len(), 1);
+
+    let payroll = keypath_pipeline!(employees() =>
+        filter(Employee::department(), |department| department == "Sales"),
+        fold(Employee::salary(), 0.0, |acc, &salary| acc + salary)
+    );
+    assert_eq!(payroll, 150.0);
+}
+
+#[test]
+fn test_join_and_smart_pointers() {
+    let projects = vec![
+        Project { owner: 2, title: "Compiler".to_string() },
+        Project { owner: 9, title: "Orphan".to_string() },
+        Project { owner: 2, title: "Linker".to_string() },
+        Project { owner: 4, title: "CRM".to_string() },
+    ];
+    let shared: Vec<Rc<Employee>> = employees().into_iter().map(Rc::new).collect();
+
+    let owners = keypath_pipeline!(shared.iter().cloned() =>
+        join(&projects, Employee::id(), Project::owner()),
+        map(|(employee, project)| format!("{}: {}", employee.name, project.title)),
+    );
+    assert_eq!(owners, vec!["Bob: Compiler", "Bob: Linker", "Dave: CRM"]);
+
+    let references = keypath_pipeline!(&shared => filter(Employee::salary(), |&salary| salary > 100.0));
+    assert_eq!(references.len(), 2);
+}
+
+#[derive(Keypath, Debug)]
+struct Member {
+    id: u32,
+    clones: Rc<std::cell::Cell<usize>>,
+}
+
+impl Clone for Member {
+    fn clone(&self) -> Self {
+        self.clones.set(self.clones.get() + 1);
+        Member { id: self.id, clones: Rc::clone(&self.clones) }
+    }
+}
+
+#[test]
+fn test_join_clones_only_for_extra_matches() {
+    let projects = vec![
+        Project { owner: 1, title: "Compiler".to_string() },
+        Project { owner: 2, title: "CRM".to_string() },
+        Project { owner: 1, title: "Linker".to_string() },
+        Project { owner: 1, title: "Debugger".to_string() },
+    ];
+    let clones = Rc::new(std::cell::Cell::new(0));
+    let members: Vec<Member> = (1..=3).map(|id| Member { id, clones: Rc::clone(&clones) }).collect();
+
+    let pairs = keypath_pipeline!(members =>
+        join(&projects, Member::id(), Project::owner()),
+        map(|(member, project)| (member.id, project.title.as_str())),
+    );
+    assert_eq!(pairs, vec![(1, "Compiler"), (1, "Linker"), (1, "Debugger"), (2, "CRM")]);
+    // Member 1 has three matches and is cloned twice; members 2 and 3 are never cloned
+    assert_eq!(clones.get(), 2);
+}
+
+#[test]
+fn test_single_operation_macro() {
+    let ids: Vec<u32> = keypath_pipeline_op!(employees().into_iter(), map(Employee::id(), |&id| id * 10)).collect();
+    assert_eq!(ids, vec![10, 20, 30, 40, 50]);
+}
+
+#[test]
+#[should_panic(expected = "KeyPath access failed in keypath_pipeline! `map`")]
+fn test_failed_access_names_operation() {
+    let _ = keypath_pipeline!(employees() => map(Employee::manager(), |&manager| manager));
+}
@@ -0,0 +1,75 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+
+#[derive(Keypath, Debug, Clone)]
+struct Sale {
+    category: String,
+    status: String,
+    revenue: f64,
+}
+
+fn sale(category: &str, status: &str, revenue: f64) -> Sale {
+    Sale { category: category.to_string(), status: status.to_string(), revenue }
+}
+
+fn sales() -> Vec<Sale> {
+    vec![
+        sale("books", "paid", 20.0),
+        sale("games", "paid", 60.0),
+        sale("books", "refunded", 15.0),
+        sale("books", "paid", 10.0),
+        sale("music", "pending", 5.0),
+    ]
+}
+
+fn sum(values: &[&f64]) -> f64 {
+    values.iter().copied().sum()
+}
+
+#[test]
+fn test_pivot_sums_with_fill_and_totals() {
+    let table = sales()
+        .pivot_by_keypaths(Sale::category(), Sale::status(), Sale::revenue(), sum, 0.0)
+        .unwrap();
+
+    assert_eq!(table.rows(), ["books", "games", "music"]);
+    assert_eq!(table.columns(), ["paid", "refunded", "pending"]);
+    assert_eq!(
+        table.cells(),
+        [vec![30.0, 15.0, 0.0], vec![60.0, 0.0, 0.0], vec![0.0, 0.0, 5.0]]
+    );
+    assert_eq!(table.get(&"books".to_string(), &"paid".to_string()), Some(&30.0));
+    assert_eq!(table.get(&"games".to_string(), &"unknown".to_string()), None);
+    assert_eq!(table.row_totals(), [45.0, 60.0, 5.0]);
+    assert_eq!(table.column_totals(), [90.0, 15.0, 5.0]);
+    assert_eq!(*table.grand_total(), 110.0);
+}
+
+#[test]
+fn test_pivot_totals_use_the_aggregation() {
+    let max = |values: &[&f64]| values.iter().fold(f64::MIN, |acc, &&value| acc.max(value));
+    let table = sales()
+        .pivot_by_keypaths(Sale::status(), Sale::category(), Sale::revenue(), max, f64::NAN)
+        .unwrap()
+        .sorted();
+
+    assert_eq!(table.rows(), ["paid", "pending", "refunded"]);
+    assert_eq!(table.columns(), ["books", "games", "music"]);
+    assert_eq!(table.cells()[0][..2], [20.0, 60.0]);
+    assert!(table.cells()[0][2].is_nan());
+    assert_eq!(table.row_totals(), [60.0, 5.0, 15.0]);
+    assert_eq!(*table.grand_total(), 60.0);
+}
+
+#[test]
+fn test_crosstab_counts() {
+    let table = sales().crosstab_by_keypaths(Sale::category(), Sale::status()).unwrap();
+    assert_eq!(table.cells(), [vec![2, 1, 0], vec![1, 0, 0], vec![0, 0, 1]]);
+    assert_eq!(table.row_totals(), [3, 1, 1]);
+    assert_eq!(table.column_totals(), [3, 1, 1]);
+    assert_eq!(*table.grand_total(), 5);
+
+    let empty = Vec::<Sale>::new().crosstab_by_keypaths(Sale::category(), Sale::status()).unwrap();
+    assert!(empty.rows().is_empty() && empty.cells().is_empty());
+    assert_eq!(*empty.grand_total(), 0);
+}
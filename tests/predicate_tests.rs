@@ -0,0 +1,108 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Employee {
+    name: String,
+    age: u32,
+    department: String,
+    address: Address,
+}
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Address {
+    city: String,
+}
+
+fn employee(name: &str, age: u32, department: &str, city: &str) -> Employee {
+    Employee {
+        name: name.to_string(),
+        age,
+        department: department.to_string(),
+        address: Address { city: city.to_string() },
+    }
+}
+
+fn sample_employees() -> Vec<Employee> {
+    vec![
+        employee("Alice", 30, "Engineering", "London"),
+        employee("Bob", 25, "Engineering", "Paris"),
+        employee("Charlie", 35, "Marketing", "London"),
+        employee("Diana", 42, "Sales", "Berlin"),
+    ]
+}
+
+fn names_matching(employees: &[Employee], predicate: &KeyPathPredicate<Employee>) -> Vec<String> {
+    employees
+        .iter()
+        .filter(|e| predicate.test(e))
+        .map(|e| e.name.clone())
+        .collect()
+}
+
+#[test]
+fn test_builder_helpers() {
+    let employees = sample_employees();
+
+    let engineers = KeyPathPredicate::field("department", Employee::department()).eq("Engineering".to_string());
+    assert_eq!(names_matching(&employees, &engineers), vec!["Alice", "Bob"]);
+
+    let over_thirty = KeyPathPredicate::on(Employee::age()).gt(30);
+    assert_eq!(names_matching(&employees, &over_thirty), vec!["Charlie", "Diana"]);
+
+    let thirties = KeyPathPredicate::on(Employee::age()).between(30, 39);
+    assert_eq!(names_matching(&employees, &thirties), vec!["Alice", "Charlie"]);
+
+    let departments = KeyPathPredicate::on(Employee::department())
+        .in_set(vec!["Sales".to_string(), "Marketing".to_string(), "Sales".to_string()]);
+    assert_eq!(names_matching(&employees, &departments), vec!["Charlie", "Diana"]);
+    assert_eq!(departments.description(), r#"value in {"Sales", "Marketing"}"#);
+
+    let has_li = KeyPathPredicate::on(Employee::name()).contains("li");
+    assert_eq!(names_matching(&employees, &has_li), vec!["Alice", "Charlie"]);
+}
+
+#[test]
+fn test_boolean_combinators() {
+    let employees = sample_employees();
+    let engineer = KeyPathPredicate::field("department", Employee::department()).eq("Engineering".to_string());
+    let senior = KeyPathPredicate::field("age", Employee::age()).ge(30);
+
+    assert_eq!(names_matching(&employees, &engineer.clone().and(senior.clone())), vec!["Alice"]);
+    assert_eq!(names_matching(&employees, &engineer.clone().or(senior.clone())), vec!["Alice", "Bob", "Charlie", "Diana"]);
+    assert_eq!(names_matching(&employees, &engineer.clone().xor(senior.clone())), vec!["Bob", "Charlie", "Diana"]);
+    assert_eq!(names_matching(&employees, &engineer.clone().not()), vec!["Charlie", "Diana"]);
+    assert_eq!(names_matching(&employees, &(!engineer & senior)), vec!["Charlie", "Diana"]);
+}
+
+#[test]
+fn test_debug_describes_predicate() {
+    let predicate = KeyPathPredicate::field("age", Employee::age())
+        .lt(40)
+        .and(KeyPathPredicate::field("name", Employee::name()).starts_with("A").not())
+        .or(KeyPathPredicate::never());
+
+    assert_eq!(format!("{:?}", predicate), "((age < 40 && !name starts_with \"A\") || false)");
+}
+
+#[test]
+fn test_lift_through_nested_keypath() {
+    let employees = sample_employees();
+    let in_london = KeyPathPredicate::field("city", Address::city())
+        .eq("London".to_string())
+        .at(Employee::address());
+
+    assert_eq!(names_matching(&employees, &in_london), vec!["Alice", "Charlie"]);
+}
+
+#[test]
+fn test_predicate_with_filter_by_keypath() {
+    let employees = sample_employees();
+    let london = KeyPathPredicate::field("city", Address::city()).eq("London".to_string());
+
+    let result = filter_by_keypath(employees.clone(), Employee::address(), london.as_fn()).unwrap();
+    assert_eq!(result.len(), 2);
+
+    let count = employees.count_by_keypath(Employee::address(), london.into_fn()).unwrap();
+    assert_eq!(count, 2);
+}
@@ -0,0 +1,132 @@
+use rust_prelude_plus::prelude::*;
+use rust_prelude_plus::query::{parse_query, CompareOp, QueryExpr, QueryRegistry, QueryValue};
+use key_paths_derive::Keypath;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Employee {
+    name: String,
+    age: u32,
+    department: String,
+    salary: f64,
+    remote: bool,
+}
+
+fn employee(name: &str, age: u32, department: &str, salary: f64, remote: bool) -> Employee {
+    Employee {
+        name: name.to_string(),
+        age,
+        department: department.to_string(),
+        salary,
+        remote,
+    }
+}
+
+fn sample_employees() -> Vec<Employee> {
+    vec![
+        employee("Alice", 30, "Engineering", 85000.0, true),
+        employee("Bob", 25, "Engineering", 65000.0, false),
+        employee("Charlie", 35, "Marketing", 70000.0, true),
+        employee("Diana", 42, "Sales", 60000.0, false),
+    ]
+}
+
+fn registry() -> QueryRegistry<Employee> {
+    QueryRegistry::new()
+        .register("name", Employee::name())
+        .register("age", Employee::age())
+        .register("department", Employee::department())
+        .register("salary", Employee::salary())
+        .register("remote", Employee::remote())
+}
+
+fn names_matching(query: &str) -> Vec<String> {
+    let predicate = registry().compile(query).unwrap();
+    let matching = filter_by_keypath(sample_employees(), identity_keypath(), predicate.as_fn()).unwrap();
+    collect_keypath(matching, Employee::name()).unwrap()
+}
+
+#[test]
+fn test_parse_query_precedence() {
+    let expr = parse_query("a == 1 || b == 2 && !c == 3").unwrap();
+    match expr {
+        QueryExpr::Or(left, right) => {
+            assert!(matches!(*left, QueryExpr::Compare { op: CompareOp::Eq, value: QueryValue::Int(1), .. }));
+            assert!(matches!(*right, QueryExpr::And(_, _)));
+        }
+        other => panic!("unexpected expression: {:?}", other),
+    }
+}
+
+#[test]
+fn test_compile_and_filter() {
+    assert_eq!(names_matching(r#"age >= 30 && department == "Engineering""#), vec!["Alice"]);
+    assert_eq!(names_matching("salary > 65000 || age > 40"), vec!["Alice", "Charlie", "Diana"]);
+    assert_eq!(names_matching("!(remote == true) && age < 30.5"), vec!["Bob"]);
+    assert_eq!(names_matching("name starts_with 'C' || name ends_with \"na\""), vec!["Charlie", "Diana"]);
+    assert_eq!(names_matching("department contains 'ing'"), vec!["Alice", "Bob", "Charlie"]);
+}
+
+#[test]
+fn test_count_and_partition() {
+    let predicate = registry().compile("remote == true").unwrap();
+    let employees = sample_employees();
+
+    assert_eq!(employees.count_by_keypath(identity_keypath(), predicate.as_fn()).unwrap(), 2);
+
+    let (remote, office) = partition_by_keypath(employees, identity_keypath(), predicate.as_fn()).unwrap();
+    assert_eq!(remote.len(), 2);
+    assert_eq!(office.len(), 2);
+}
+
+#[test]
+fn test_custom_adapter() {
+    let registry = QueryRegistry::new()
+        .register_with("name_length", Employee::name(), |name: &String| QueryValue::Int(name.len() as i128));
+    let predicate = registry.compile("name_length <= 3").unwrap();
+
+    let matching = filter_by_keypath(sample_employees(), identity_keypath(), predicate.as_fn()).unwrap();
+    assert_eq!(matching.len(), 1);
+    assert_eq!(matching[0].name, "Bob");
+}
+
+#[test]
+fn test_parse_errors() {
+    let cases = [
+        ("age >", "expected a value after '>', found end of query", 5),
+        ("age = 3", "unexpected '=', did you mean '=='?", 4),
+        ("age 3", "expected a comparison operator after 'age', found 3", 4),
+        ("(age > 3", "expected ')', found end of query", 8),
+        ("name == \"Al", "unterminated string literal", 8),
+        ("age > 3 age", "unexpected 'age' after end of expression", 8),
+    ];
+
+    for (query, message, position) in cases {
+        match parse_query(query) {
+            Err(KeyPathError::QueryError { message: actual, position: actual_position }) => {
+                assert_eq!(actual, message, "query: {}", query);
+                assert_eq!(actual_position, position, "query: {}", query);
+            }
+            other => panic!("expected query error for {:?}, got {:?}", query, other),
+        }
+    }
+}
+
+#[test]
+fn test_compile_errors() {
+    let registry = registry();
+
+    let error = registry.compile("age > 3 && agee < 5").unwrap_err();
+    assert_eq!(
+        error,
+        KeyPathError::QueryError {
+            message: "unknown field 'agee' (known fields: age, department, name, remote, salary)".to_string(),
+            position: 11,
+        }
+    );
+
+    let error = registry.compile("age == 'thirty'").unwrap_err();
+    assert!(error.to_string().contains("cannot compare number field 'age' with string value"));
+
+    let error = registry.compile("age contains 3").unwrap_err();
+    assert!(error.to_string().contains("operator 'contains' requires a string field"));
+}
@@ -0,0 +1,82 @@
+use key_paths_core::KeyPaths;
+use key_paths_derive::Keypath;
+use rust_prelude_plus::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Payment {
+    Card { last4: String },
+    Cash,
+}
+
+#[derive(Keypath, Debug, Clone)]
+struct Customer {
+    name: String,
+    email: Option<String>,
+    payment: Payment,
+}
+
+fn card_last4() -> KeyPaths<Customer, String> {
+    KeyPaths::failable_readable(|customer: &Customer| match &customer.payment {
+        Payment::Card { last4 } => Some(last4),
+        Payment::Cash => None,
+    })
+}
+
+fn customer(name: &str, email: Option<&str>, payment: Payment) -> Customer {
+    Customer {
+        name: name.to_string(),
+        email: email.map(str::to_string),
+        payment,
+    }
+}
+
+fn customers() -> Vec<Customer> {
+    vec![
+        customer("Alice", Some("alice@example.com"), Payment::Card { last4: "4242".to_string() }),
+        customer("Bob", None, Payment::Cash),
+        customer("Carol", Some("carol@example.com"), Payment::Cash),
+        customer("Dave", None, Payment::Card { last4: "x1".to_string() }),
+    ]
+}
+
+#[test]
+fn test_map_keypath_collect_errors_partitions_results() {
+    let customers = customers();
+    let (emails, errors) = map_keypath_collect_errors(&customers, Customer::email(), |email| email.to_uppercase());
+    assert_eq!(emails, vec!["ALICE@EXAMPLE.COM", "CAROL@EXAMPLE.COM"]);
+    let indices: Vec<usize> = errors.iter().map(|(index, _)| *index).collect();
+    assert_eq!(indices, vec![1, 3]);
+    assert!(errors.iter().all(|(_, error)| matches!(error, KeyPathError::InvalidAccess { .. })));
+
+    let (digits, errors) = try_map_keypath_collect_errors(&customers, card_last4(), |last4| last4.parse::<u16>());
+    assert_eq!(digits, vec![4242]);
+    assert!(matches!(errors[0], (1, KeyPathError::InvalidAccess { .. })));
+    assert!(matches!(errors[1], (2, KeyPathError::InvalidAccess { .. })));
+    assert!(matches!(errors[2], (3, KeyPathError::ClosureError { index: 3, .. })));
+}
+
+#[test]
+fn test_report_counts_failures_per_keypath() {
+    let customers = customers();
+    let mut report = KeyPathReport::new(customers.len())
+        .check("name", &customers, Customer::name())
+        .check("email", &customers, Customer::email());
+    let (_, errors) = try_map_keypath_collect_errors(&customers, card_last4(), |last4| last4.parse::<u16>());
+    report.record("card", &errors);
+
+    assert_eq!(report.total(), 4);
+    assert_eq!(report.failure_counts(), vec![("name", 0), ("email", 2), ("card", 3)]);
+    assert_eq!(report.failed_indices("card"), &[1, 2, 3]);
+    assert_eq!(report.failures("missing"), 0);
+    assert_eq!(report.failed_elements(), 3);
+    assert!(!report.is_clean());
+    assert_eq!(report.to_string(), "3 of 4 elements failed: name 0, email 2, card 3");
+
+    // Recording the same keypath again merges indices rather than double counting
+    report.record("card", &errors);
+    assert_eq!(report.failures("card"), 3);
+
+    let clean = KeyPathReport::new(customers.len()).check("name", &customers, Customer::name());
+    assert!(clean.is_clean());
+    assert_eq!(clean.to_string(), "0 of 4 elements failed: name 0");
+}
@@ -0,0 +1,70 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+use std::collections::VecDeque;
+
+#[derive(Keypath, Debug, Clone)]
+struct Tick {
+    price: f64,
+    volume: u32,
+}
+
+fn ticks() -> Vec<Tick> {
+    [(10.0, 5), (12.0, 0), (11.0, 3), (15.0, 2)]
+        .into_iter()
+        .map(|(price, volume)| Tick { price, volume })
+        .collect()
+}
+
+#[test]
+fn test_scan_keypath_on_collections_and_slices() {
+    let ticks = ticks();
+    let running = ticks.scan_keypath(Tick::volume(), 0, |acc, &volume| acc + volume).unwrap();
+    assert_eq!(running, vec![5, 5, 8, 10]);
+
+    let labels = scan_keypath(&ticks, Tick::price(), String::new(), |acc, price| {
+        format!("{}{}", acc, if *price > 11.0 { "+" } else { "-" })
+    })
+    .unwrap();
+    assert_eq!(labels, vec!["-", "-+", "-+-", "-+-+"]);
+}
+
+#[test]
+fn test_cumulative_operations() {
+    let ticks = ticks();
+    assert_eq!(ticks.cumulative_sum_by_keypath(Tick::volume()).unwrap(), vec![5, 5, 8, 10]);
+    assert_eq!(ticks.cumulative_max_by_keypath(Tick::price()).unwrap(), vec![10.0, 12.0, 12.0, 15.0]);
+    assert_eq!(
+        ticks.running_count_by_keypath(Tick::volume(), |&volume| volume > 0).unwrap(),
+        vec![1, 1, 2, 3]
+    );
+
+    let empty: VecDeque<Tick> = VecDeque::new();
+    assert!(empty.cumulative_sum_by_keypath(Tick::price()).unwrap().is_empty());
+}
+
+#[test]
+fn test_diff_by_keypath() {
+    let ticks = ticks();
+    assert_eq!(ticks.diff_by_keypath(Tick::price()).unwrap(), vec![2.0, -1.0, 4.0]);
+    assert!(ticks[..1].diff_by_keypath(Tick::price()).unwrap().is_empty());
+    // Same values as a window of two
+    assert_eq!(
+        ticks.diff_by_keypath(Tick::price()).unwrap(),
+        ticks.window_by_keypath(Tick::price(), 2, |pair| pair[1] - pair[0]).unwrap()
+    );
+}
+
+#[test]
+fn test_diff_by_keypath_with_unsigned_values() {
+    let ticks = ticks();
+    // Volumes are u32 and go down from 5 to 0, so a plain difference would overflow
+    assert_eq!(
+        ticks.diff_by_keypath_with(Tick::volume(), |current, previous| i64::from(current) - i64::from(previous)).unwrap(),
+        vec![-5, 3, -1]
+    );
+    assert_eq!(
+        ticks.diff_by_keypath_with(Tick::volume(), u32::checked_sub).unwrap(),
+        vec![None, Some(3), None]
+    );
+    assert_eq!(ticks[1..3].diff_by_keypath(Tick::volume()).unwrap(), vec![3]);
+}
@@ -0,0 +1,126 @@
+use rust_prelude_plus::prelude::*;
+use std::collections::HashMap;
+
+#[derive(KeyPathSchema, Debug, Clone)]
+struct Geo {
+    lat: f64,
+    lon: f64,
+}
+
+#[derive(KeyPathSchema, Debug, Clone)]
+struct Address {
+    city: String,
+    #[keypath_schema(nested)]
+    geo: Geo,
+}
+
+#[derive(KeyPathSchema, Debug, Clone)]
+struct Customer {
+    #[keypath_schema(rename = "customer_id")]
+    id: u64,
+    name: String,
+    nickname: Option<String>,
+    scores: HashMap<String, u32>,
+    #[keypath_schema(skip)]
+    #[allow(dead_code)]
+    password_hash: String,
+    #[keypath_schema(nested)]
+    address: Address,
+}
+
+#[derive(KeyPathSchema, Debug, Clone)]
+struct Pair(u32, #[keypath_schema(debug)] String);
+
+fn sample_customer() -> Customer {
+    Customer {
+        id: 7,
+        name: "Alice".to_string(),
+        nickname: None,
+        scores: HashMap::from([("level".to_string(), 3)]),
+        password_hash: "secret".to_string(),
+        address: Address {
+            city: "Paris".to_string(),
+            geo: Geo { lat: 48.85, lon: 2.35 },
+        },
+    }
+}
+
+#[test]
+fn test_static_field_metadata() {
+    assert_eq!(Customer::FIELD_NAMES, &["customer_id", "name", "nickname", "scores", "address"]);
+    assert_eq!(
+        Customer::FIELD_TYPES,
+        &["u64", "String", "Option<String>", "HashMap<String, u32>", "Address"]
+    );
+    assert_eq!(Pair::FIELD_NAMES, &["0", "1"]);
+}
+
+#[test]
+fn test_nested_fields_are_flattened() {
+    assert_eq!(
+        Customer::field_paths(),
+        vec!["customer_id", "name", "nickname", "scores", "address.city", "address.geo.lat", "address.geo.lon"]
+    );
+
+    let schema = Customer::schema();
+    let lat = schema.iter().find(|field| field.name() == "address.geo.lat").unwrap();
+    assert_eq!(lat.type_name(), "f64");
+    assert!(lat.keypath().is::<f64>());
+}
+
+#[test]
+fn test_schema_keypaths_read_values() {
+    let customer = sample_customer();
+    let registry = Customer::keypath_registry();
+
+    let rendered: Vec<String> = registry
+        .iter()
+        .map(|(_, keypath)| keypath.get(&customer).unwrap().to_string())
+        .collect();
+    assert_eq!(
+        rendered,
+        vec!["7", "Alice", "None", "{\"level\": 3}", "Paris", "48.85", "2.35"]
+    );
+
+    let lon = registry.get_value(&customer, "address.geo.lon").unwrap();
+    assert_eq!(lon.downcast_ref::<f64>(), Some(&2.35));
+    assert!(registry.get("password_hash").is_none());
+}
+
+#[test]
+fn test_schema_drives_runtime_selection() {
+    let customers = vec![sample_customer(), Customer { id: 8, name: "Bob".to_string(), ..sample_customer() }];
+    let registry = Customer::keypath_registry();
+
+    let rows = select_by_names(&customers, &registry, &["customer_id", "address.city"]).unwrap();
+    let rendered: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|value| value.to_string()).collect())
+        .collect();
+    assert_eq!(rendered, vec![vec!["7", "Paris"], vec!["8", "Paris"]]);
+}
+
+#[test]
+fn test_tuple_struct_debug_attribute() {
+    let pair = Pair(1, "one".to_string());
+    let registry = Pair::keypath_registry();
+    assert_eq!(registry.get_value(&pair, "0").unwrap().to_string(), "1");
+    assert_eq!(registry.get_value(&pair, "1").unwrap().to_string(), "\"one\"");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize_attribute() {
+    #[derive(KeyPathSchema, Debug, Clone)]
+    struct Row {
+        #[keypath_schema(serialize)]
+        name: String,
+        #[keypath_schema(serialize)]
+        count: u32,
+    }
+
+    let rows = vec![Row { name: "a".to_string(), count: 1 }];
+    let registry = Row::keypath_registry();
+    let selected = select_by_names(&rows, &registry, &["name", "count"]).unwrap();
+    assert_eq!(serde_json::to_string(&selected).unwrap(), r#"[["a",1]]"#);
+}
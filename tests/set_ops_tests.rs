@@ -0,0 +1,69 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct User {
+    email: String,
+    name: String,
+}
+
+#[derive(Keypath, Debug, Clone)]
+struct Unsubscribe {
+    address: String,
+}
+
+fn user(email: &str, name: &str) -> User {
+    User { email: email.to_string(), name: name.to_string() }
+}
+
+fn names(users: &[User]) -> Vec<&str> {
+    users.iter().map(|user| user.name.as_str()).collect()
+}
+
+fn left() -> Vec<User> {
+    vec![user("a@x", "Ann"), user("b@x", "Ben"), user("a@x", "Ann again"), user("c@x", "Cat")]
+}
+
+fn right() -> Vec<User> {
+    vec![user("d@x", "Dan"), user("b@x", "Bob"), user("e@x", "Eve")]
+}
+
+#[test]
+fn test_union_and_intersection() {
+    let union = left().union_by_keypath(&right(), User::email()).unwrap();
+    assert_eq!(names(&union), vec!["Ann", "Ben", "Cat", "Dan", "Eve"]);
+
+    let both = left().intersect_by_keypath(&right(), User::email()).unwrap();
+    assert_eq!(names(&both), vec!["Ben"]);
+    let both = right().intersect_by_keypath(&left(), User::email()).unwrap();
+    assert_eq!(names(&both), vec!["Bob"]);
+}
+
+#[test]
+fn test_difference_and_symmetric_difference() {
+    let only_left = left().difference_by_keypath(&right(), User::email()).unwrap();
+    assert_eq!(names(&only_left), vec!["Ann", "Cat"]);
+
+    let either = left().symmetric_difference_by_keypath(&right(), User::email()).unwrap();
+    assert_eq!(names(&either), vec!["Ann", "Cat", "Dan", "Eve"]);
+
+    assert!(left().difference_by_keypath(&left(), User::email()).unwrap().is_empty());
+    assert_eq!(left().union_by_keypath(&[], User::email()).unwrap().len(), 3);
+}
+
+#[test]
+fn test_cross_type_set_operations() {
+    let unsubscribed = vec![
+        Unsubscribe { address: "c@x".to_string() },
+        Unsubscribe { address: "z@x".to_string() },
+    ];
+    let keep = left()
+        .difference_by_keypaths(&unsubscribed, User::email(), Unsubscribe::address())
+        .unwrap();
+    assert_eq!(names(&keep), vec!["Ann", "Ben"]);
+
+    let dropped = left()
+        .intersect_by_keypaths(&unsubscribed, User::email(), Unsubscribe::address())
+        .unwrap();
+    assert_eq!(names(&dropped), vec!["Cat"]);
+}
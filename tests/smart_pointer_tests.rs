@@ -0,0 +1,99 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Person {
+    name: String,
+    age: u32,
+}
+
+fn person(name: &str, age: u32) -> Person {
+    Person { name: name.to_string(), age }
+}
+
+fn rc_people() -> Vec<Rc<Person>> {
+    vec![Rc::new(person("Alice", 30)), Rc::new(person("Bob", 25)), Rc::new(person("Charlie", 35))]
+}
+
+#[test]
+fn test_higher_order_returns_rc_handles() {
+    let people = rc_people();
+    let adults = filter_by_keypath(people.clone(), Person::age(), |&age| age >= 30).unwrap();
+    assert_eq!(adults.len(), 2);
+    assert!(Rc::ptr_eq(&adults[0], &people[0]));
+    assert!(Rc::ptr_eq(&adults[1], &people[2]));
+
+    let found = find_by_keypath(people.clone(), Person::name(), |name| name == "Bob").unwrap().unwrap();
+    assert!(Rc::ptr_eq(&found, &people[1]));
+
+    let grouped = group_by_keypath(&people, Person::age(), |&age| age >= 30).unwrap();
+    assert!(Rc::ptr_eq(&grouped[&false][0], &people[1]));
+
+    let total = fold_keypath(people.clone(), Person::age(), 0, |acc, &age| acc + age).unwrap();
+    assert_eq!(total, 90);
+}
+
+#[test]
+fn test_higher_order_accepts_borrowed_and_boxed_items() {
+    let owned = [person("Alice", 30), person("Bob", 25)];
+    let borrowed: Vec<&Person> = owned.iter().collect();
+    let (young, old) = partition_by_keypath(borrowed, Person::age(), |&age| age < 30).unwrap();
+    assert!(std::ptr::eq(young[0], &owned[1]));
+    assert!(std::ptr::eq(old[0], &owned[0]));
+
+    let mut boxed: Vec<Box<Person>> = owned.iter().cloned().map(Box::new).collect();
+    sort_by_keypath(&mut boxed, Person::age(), |a, b| a.cmp(b)).unwrap();
+    assert_eq!(collect_keypath(boxed, Person::name()).unwrap(), vec!["Bob", "Alice"]);
+
+    let shared: Vec<Arc<Person>> = owned.iter().cloned().map(Arc::new).collect();
+    let names = map_keypath_collection(&shared, Person::name(), |name| name.len()).unwrap();
+    assert_eq!(names, vec![5, 3]);
+
+    let cows: Vec<Cow<'_, Person>> = vec![Cow::Borrowed(&owned[0]), Cow::Owned(person("Dana", 41))];
+    assert_eq!(collect_keypath(cows, Person::age()).unwrap(), vec![30, 41]);
+
+    let upper = map_keypath(Rc::new(person("Eve", 22)), Person::name(), |name| name.to_uppercase()).unwrap();
+    assert_eq!(upper, "EVE");
+}
+
+#[test]
+fn test_iterator_extensions_over_references_to_handles() {
+    let people = rc_people();
+
+    let names = people.iter().map_keypath(Person::name(), |name| name.clone());
+    assert_eq!(names, vec!["Alice", "Bob", "Charlie"]);
+
+    let older: Vec<&Rc<Person>> = people.iter().filter_by_keypath(Person::age(), |&age| age > 28);
+    assert_eq!(older.len(), 2);
+
+    let youngest = people.iter().find_by_keypath(Person::age(), |&age| age < 30).unwrap().unwrap();
+    assert_eq!(youngest.name, "Bob");
+
+    let ages = people.clone().into_iter().collect_keypath(Person::age()).unwrap();
+    assert_eq!(ages, vec![30, 25, 35]);
+
+    let keypath = Person::age();
+    assert_eq!(people[0].get_at_keypath(&keypath).unwrap(), &30);
+}
+
+#[test]
+fn test_collection_extensions_over_handles() {
+    let mut people = rc_people();
+
+    let (older, younger) = KeyPathsCollectionExt::partition_by_keypath(&people, Person::age(), |&age| age >= 30).unwrap();
+    assert!(Rc::ptr_eq(&younger[0], &people[1]));
+    assert_eq!(older.len(), 2);
+
+    assert_eq!(people.count_by_keypath(Person::age(), |&age| age > 26).unwrap(), 2);
+    KeyPathsCollectionExt::sort_by_keypath(&mut people, Person::age(), |a, b| b.cmp(a)).unwrap();
+    assert_eq!(people.collect_keypath(Person::name()).unwrap(), vec!["Charlie", "Alice", "Bob"]);
+
+    let chained: Vec<String> = chain_keypath_ops(rc_people())
+        .filter_by_keypath(Person::age(), |&age| age < 35)
+        .map_keypath(Person::name(), |name| name.clone())
+        .collect();
+    assert_eq!(chained, vec!["Alice", "Bob"]);
+}
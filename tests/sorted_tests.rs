@@ -0,0 +1,81 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+use std::collections::VecDeque;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Order {
+    id: u32,
+    amount: f64,
+}
+
+fn order(id: u32, amount: f64) -> Order {
+    Order { id, amount }
+}
+
+fn by_amount(a: &f64, b: &f64) -> std::cmp::Ordering {
+    a.partial_cmp(b).unwrap()
+}
+
+fn sorted_orders() -> Vec<Order> {
+    let mut orders = vec![order(1, 30.0), order(2, 10.0), order(3, 20.0), order(4, 20.0), order(5, 50.0), order(6, 20.0)];
+    sort_by_keypath(&mut orders, Order::amount(), by_amount).unwrap();
+    orders
+}
+
+#[test]
+fn test_binary_search_and_partition_point() {
+    let orders = sorted_orders();
+    assert!(orders.is_sorted_by_keypath(Order::amount(), by_amount).unwrap());
+
+    let found = orders.binary_search_by_keypath(Order::amount(), &30.0, by_amount).unwrap();
+    assert_eq!(found, Ok(4));
+    assert_eq!(orders.binary_search_by_keypath(Order::amount(), &40.0, by_amount).unwrap(), Err(5));
+    assert_eq!(orders.binary_search_by_keypath(Order::amount(), &5.0, by_amount).unwrap(), Err(0));
+
+    let cheap = orders.partition_point_by_keypath(Order::amount(), |&amount| amount < 25.0).unwrap();
+    assert_eq!(cheap, 4);
+}
+
+#[test]
+fn test_equal_range_by_keypath() {
+    let orders = sorted_orders();
+    let range = orders.equal_range_by_keypath(Order::amount(), &20.0, by_amount).unwrap();
+    assert_eq!(range, 1..4);
+    // Sorting is stable, so equal amounts keep their original order
+    let ids: Vec<u32> = orders[range].iter().map(|order| order.id).collect();
+    assert_eq!(ids, vec![3, 4, 6]);
+
+    assert_eq!(orders.equal_range_by_keypath(Order::amount(), &25.0, by_amount).unwrap(), 4..4);
+
+    let deque: VecDeque<Order> = orders.into_iter().collect();
+    assert_eq!(deque.equal_range_by_keypath(Order::amount(), &50.0, by_amount).unwrap(), 5..6);
+    assert_eq!(deque.binary_search_by_keypath(Order::amount(), &10.0, by_amount).unwrap(), Ok(0));
+}
+
+#[test]
+fn test_is_sorted_by_keypath() {
+    let unsorted = [order(1, 30.0), order(2, 10.0)];
+    assert!(!unsorted.is_sorted_by_keypath(Order::amount(), by_amount).unwrap());
+    assert!(unsorted.is_sorted_by_keypath(Order::id(), |a, b| a.cmp(b)).unwrap());
+    assert!(Vec::<Order>::new().is_sorted_by_keypath(Order::amount(), by_amount).unwrap());
+}
+
+#[test]
+fn test_merge_sorted_by_keypath() {
+    let merged = merge_sorted_by_keypath(
+        vec![
+            vec![order(1, 10.0), order(2, 20.0), order(3, 40.0)],
+            vec![],
+            vec![order(4, 5.0), order(5, 20.0), order(6, 60.0)],
+            vec![order(7, 20.0)],
+        ],
+        Order::amount(),
+        by_amount,
+    )
+    .unwrap();
+    let ids: Vec<u32> = merged.iter().map(|order| order.id).collect();
+    assert_eq!(ids, vec![4, 1, 2, 5, 7, 3, 6]);
+    assert!(merged.is_sorted_by_keypath(Order::amount(), by_amount).unwrap());
+
+    assert!(merge_sorted_by_keypath(Vec::<Vec<Order>>::new(), Order::amount(), by_amount).unwrap().is_empty());
+}
@@ -0,0 +1,114 @@
+use rust_prelude_plus::prelude::*;
+use key_paths_derive::Keypath;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Product {
+    name: String,
+    price: f64,
+}
+
+fn products() -> Vec<Product> {
+    [("Laptop", 999.0), ("Mouse", 25.0), ("Desk", 300.0), ("Pen", 2.0), ("Chair", 300.0), ("Lamp", 45.0)]
+        .into_iter()
+        .map(|(name, price)| Product { name: name.to_string(), price })
+        .collect()
+}
+
+fn names<'a>(products: impl IntoIterator<Item = &'a Product>) -> Vec<&'a str> {
+    products.into_iter().map(|product| product.name.as_str()).collect()
+}
+
+fn by_price(a: &f64, b: &f64) -> std::cmp::Ordering {
+    a.partial_cmp(b).unwrap()
+}
+
+#[test]
+fn test_top_and_bottom_k_on_collections() {
+    let products = products();
+
+    let top = products.top_k_by_keypath(Product::price(), 3, by_price).unwrap();
+    assert_eq!(names(top), vec!["Laptop", "Desk", "Chair"]);
+
+    let bottom = products.bottom_k_by_keypath(Product::price(), 2, by_price).unwrap();
+    assert_eq!(names(bottom), vec!["Pen", "Mouse"]);
+
+    assert!(products.top_k_by_keypath(Product::price(), 0, by_price).unwrap().is_empty());
+    assert_eq!(products.bottom_k_by_keypath(Product::price(), 10, by_price).unwrap().len(), 6);
+}
+
+#[test]
+fn test_k_larger_than_input_keeps_everything() {
+    let products = products();
+    for k in [7, 1 << 40, usize::MAX] {
+        let top = products.top_k_by_keypath(Product::price(), k, by_price).unwrap();
+        assert_eq!(names(top), vec!["Laptop", "Desk", "Chair", "Lamp", "Mouse", "Pen"]);
+
+        let bottom = products.iter().bottom_k_by_keypath(Product::price(), k, by_price);
+        assert_eq!(bottom.len(), 6);
+    }
+}
+
+#[test]
+fn test_top_k_matches_sort_then_take() {
+    let products = products();
+    for k in 0..=products.len() {
+        let mut sorted = products.clone();
+        sorted.sort_by(|a, b| by_price(&b.price, &a.price));
+        let expected: Vec<&str> = names(&sorted).into_iter().take(k).collect();
+        assert_eq!(names(products.top_k_by_keypath(Product::price(), k, by_price).unwrap()), expected);
+    }
+}
+
+#[test]
+fn test_top_and_bottom_k_on_iterators() {
+    let top = products().into_iter().top_k_by_keypath(Product::price(), 2, by_price);
+    assert_eq!(names(&top), vec!["Laptop", "Desk"]);
+
+    let products = products();
+    let bottom = products.iter().bottom_k_by_keypath(Product::price(), 3, by_price);
+    assert_eq!(names(bottom), vec!["Pen", "Mouse", "Lamp"]);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_top_and_bottom_k() {
+    use rust_prelude_plus::parallel::parallel_collections::{par_bottom_k_by_keypath, par_top_k_by_keypath};
+
+    let many: Vec<Product> = (0..1000)
+        .map(|i| Product { name: format!("p{}", i), price: ((i * 37) % 1000) as f64 })
+        .collect();
+    let expected = many.top_k_by_keypath(Product::price(), 5, by_price).unwrap();
+    let expected: Vec<Product> = expected.into_iter().cloned().collect();
+    assert_eq!(par_top_k_by_keypath(many.clone(), Product::price(), 5, by_price).unwrap(), expected);
+
+    let bottom = par_bottom_k_by_keypath(products(), Product::price(), 2, by_price).unwrap();
+    assert_eq!(names(&bottom), vec!["Pen", "Mouse"]);
+
+    let all = par_top_k_by_keypath(products(), Product::price(), usize::MAX, by_price).unwrap();
+    assert_eq!(names(&all), vec!["Laptop", "Desk", "Chair", "Lamp", "Mouse", "Pen"]);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_top_k_reads_computed_values_once() {
+    use rust_prelude_plus::parallel::parallel_collections::par_top_k_by_keypath;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let label = computed_keypath(move |product: &Product| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        format!("{}:{}", product.name, product.price)
+    });
+    let by_len = |a: &String, b: &String| a.len().cmp(&b.len()).then(b.cmp(a));
+
+    let products = products();
+    let top = products.top_k_by_keypath(label.clone(), 2, by_len).unwrap();
+    assert_eq!(names(top), vec!["Laptop", "Chair"]);
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+
+    let top = par_top_k_by_keypath(products, label, 2, by_len).unwrap();
+    assert_eq!(names(&top), vec!["Laptop", "Chair"]);
+    assert_eq!(calls.load(Ordering::SeqCst), 12);
+}
@@ -0,0 +1,110 @@
+use key_paths_core::KeyPaths;
+use key_paths_derive::Keypath;
+use rust_prelude_plus::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Product {
+    name: String,
+    price: f64,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Order {
+    id: u32,
+    products: Vec<Product>,
+    discounts: HashMap<String, f64>,
+    coupon: Option<String>,
+}
+
+fn products() -> KeyPaths<Order, Vec<Product>> {
+    KeyPaths::readable(|order: &Order| &order.products)
+}
+
+fn product(name: &str, price: f64, tags: &[&str]) -> Product {
+    Product {
+        name: name.to_string(),
+        price,
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+    }
+}
+
+fn orders() -> Vec<Order> {
+    vec![
+        Order {
+            id: 1,
+            products: vec![product("Laptop", 999.0, &["electronics"]), product("Mouse", 25.0, &["electronics", "sale"])],
+            discounts: HashMap::from([("spring".to_string(), 0.1)]),
+            coupon: Some("WELCOME".to_string()),
+        },
+        Order {
+            id: 2,
+            products: vec![],
+            discounts: HashMap::new(),
+            coupon: None,
+        },
+        Order {
+            id: 3,
+            products: vec![product("Book", 15.0, &["sale"])],
+            discounts: HashMap::from([("spring".to_string(), 0.2), ("vip".to_string(), 0.05)]),
+            coupon: None,
+        },
+    ]
+}
+
+#[test]
+fn test_each_then_reaches_every_nested_value() {
+    let orders = orders();
+    let prices = products().each().then(Product::price());
+
+    assert_eq!(prices.get_all(&orders[0]), vec![&999.0, &25.0]);
+    assert_eq!(prices.count(&orders[1]), 0);
+    assert_eq!(flatten_keypath(&orders, prices.clone()).unwrap(), vec![&999.0, &25.0, &15.0]);
+
+    let total = fold_flat_keypath(&orders, prices, 0.0, |acc, &price| acc + price).unwrap();
+    assert_eq!(total, 1039.0);
+}
+
+#[test]
+fn test_nested_each_and_filters() {
+    let orders = orders();
+    let tags = products()
+        .each()
+        .then(KeyPaths::readable(|product: &Product| &product.tags))
+        .each();
+
+    let all_tags = flat_map_keypath(&orders, tags.clone(), |tag| tag.clone()).unwrap();
+    assert_eq!(all_tags, vec!["electronics", "electronics", "sale", "sale"]);
+
+    let sale = flatten_keypath(&orders, tags.filter(|tag| tag == "sale")).unwrap();
+    assert_eq!(sale.len(), 2);
+
+    let cheap_orders = filter_by_flat_keypath(orders, products().each().then(Product::price()), |&price| price < 20.0).unwrap();
+    let ids: Vec<u32> = cheap_orders.iter().map(|order| order.id).collect();
+    assert_eq!(ids, vec![3]);
+}
+
+#[test]
+fn test_index_and_key_keypaths() {
+    let orders = orders();
+    let second_name = products().index(1).then(Product::name());
+    assert_eq!(second_name.get(&orders[0]).unwrap(), "Mouse");
+    assert!(second_name.get(&orders[2]).is_none());
+
+    let spring = KeyPaths::readable(|order: &Order| &order.discounts).key("spring".to_string());
+    let springs = flatten_keypath(&orders, spring.into()).unwrap();
+    assert_eq!(springs, vec![&0.1, &0.2]);
+}
+
+#[test]
+fn test_option_and_map_traversals() {
+    let orders = orders();
+    let coupons = KeyPaths::readable(|order: &Order| &order.coupon).each();
+    assert_eq!(flatten_keypath(&orders, coupons).unwrap(), vec!["WELCOME"]);
+
+    let discounts = KeyPaths::readable(|order: &Order| &order.discounts).each();
+    let mut values = flatten_keypath(&orders, discounts).unwrap();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(values, vec![&0.05, &0.1, &0.2]);
+}
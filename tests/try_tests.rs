@@ -0,0 +1,122 @@
+use key_paths_derive::Keypath;
+use rust_prelude_plus::prelude::*;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Row {
+    id: u32,
+    amount: String,
+}
+
+fn row(id: u32, amount: &str) -> Row {
+    Row {
+        id,
+        amount: amount.to_string(),
+    }
+}
+
+fn rows() -> Vec<Row> {
+    vec![row(1, "10"), row(2, "abc"), row(3, "30"), row(4, "")]
+}
+
+fn parse(amount: &str) -> Result<i64, std::num::ParseIntError> {
+    amount.parse::<i64>()
+}
+
+fn closure_indices(error: KeyPathError) -> Vec<usize> {
+    match error {
+        KeyPathError::Multiple { errors } => errors
+            .into_iter()
+            .map(|error| match error {
+                KeyPathError::ClosureError { index, .. } => index,
+                other => panic!("unexpected error: {:?}", other),
+            })
+            .collect(),
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_try_map_fail_fast_and_collect_all() {
+    let rows = rows();
+    let ok = try_map_keypath_collection(&rows[..1], Row::amount(), |amount| parse(amount), ErrorMode::FailFast).unwrap();
+    assert_eq!(ok, vec![10]);
+
+    let error = try_map_keypath_collection(&rows, Row::amount(), |amount| parse(amount), ErrorMode::FailFast).unwrap_err();
+    match error {
+        KeyPathError::ClosureError { index, message, source } => {
+            assert_eq!(index, 1);
+            assert_eq!(message, "invalid digit found in string");
+            let parse_error = source.downcast_ref::<std::num::ParseIntError>().unwrap();
+            assert_eq!(parse_error.kind(), &std::num::IntErrorKind::InvalidDigit);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    let error = try_map_keypath_collection(&rows, Row::amount(), |amount| parse(amount), ErrorMode::CollectAll).unwrap_err();
+    assert_eq!(error.to_string(), "2 errors occurred");
+    assert_eq!(closure_indices(error), vec![1, 3]);
+
+    assert_eq!(try_map_keypath(row(9, "7"), Row::amount(), |amount| parse(amount)).unwrap(), 7);
+}
+
+#[test]
+fn test_try_filter_and_fold() {
+    let valid = vec![row(1, "10"), row(2, "25"), row(3, "30")];
+    let large = try_filter_by_keypath(valid.clone(), Row::amount(), |amount| parse(amount).map(|n| n > 15), ErrorMode::FailFast).unwrap();
+    let ids: Vec<u32> = large.iter().map(|row| row.id).collect();
+    assert_eq!(ids, vec![2, 3]);
+
+    let error = try_filter_by_keypath(rows(), Row::amount(), |amount| parse(amount).map(|n| n > 15), ErrorMode::CollectAll).unwrap_err();
+    assert_eq!(closure_indices(error), vec![1, 3]);
+
+    let total = try_fold_keypath(valid, Row::amount(), 0, |acc, amount| parse(amount).map(|n| acc + n)).unwrap();
+    assert_eq!(total, 65);
+
+    let error = try_fold_keypath(rows(), Row::amount(), 0, |acc, amount| parse(amount).map(|n| acc + n)).unwrap_err();
+    assert!(matches!(error, KeyPathError::ClosureError { index: 1, .. }));
+}
+
+#[test]
+fn test_try_operations_on_iterators_and_chains() {
+    let rows = rows();
+    let error = rows
+        .iter()
+        .try_map_keypath(Row::amount(), |amount| parse(amount), ErrorMode::CollectAll)
+        .unwrap_err();
+    assert_eq!(closure_indices(error), vec![1, 3]);
+
+    let kept = rows
+        .iter()
+        .try_filter_by_keypath(Row::amount(), |amount| Ok::<_, std::fmt::Error>(!amount.is_empty()), ErrorMode::FailFast)
+        .unwrap();
+    assert_eq!(kept.len(), 3);
+
+    let total = chain_keypath_ops(rows.clone())
+        .filter_by_keypath(Row::id(), |&id| id != 2 && id != 4)
+        .try_map_keypath(Row::amount(), |amount| parse(amount), ErrorMode::FailFast)
+        .unwrap()
+        .collect::<Vec<i64>>();
+    assert_eq!(total, vec![10, 30]);
+
+    let error = chain_keypath_ops(rows)
+        .try_fold_keypath(Row::amount(), 0, |acc, amount| parse(amount).map(|n| acc + n))
+        .unwrap_err();
+    assert!(matches!(error, KeyPathError::ClosureError { index: 1, .. }));
+}
+
+#[test]
+fn test_iterator_try_operations_stop_at_first_error() {
+    // An endless iterator: only failing fast can make these calls return
+    let endless = || (1u32..).map(|id| row(id, if id == 3 { "x" } else { "1" }));
+
+    let error = endless().try_map_keypath(Row::amount(), |amount| parse(amount), ErrorMode::FailFast).unwrap_err();
+    assert!(matches!(error, KeyPathError::ClosureError { index: 2, .. }));
+
+    let error = endless()
+        .try_filter_by_keypath(Row::amount(), |amount| parse(amount).map(|n| n > 0), ErrorMode::FailFast)
+        .unwrap_err();
+    assert!(matches!(error, KeyPathError::ClosureError { index: 2, .. }));
+
+    let error = endless().try_fold_keypath(Row::amount(), 0, |acc, amount| parse(amount).map(|n| acc + n)).unwrap_err();
+    assert!(matches!(error, KeyPathError::ClosureError { index: 2, .. }));
+}
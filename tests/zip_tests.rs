@@ -0,0 +1,100 @@
+use key_paths_derive::Keypath;
+use rust_prelude_plus::prelude::*;
+
+#[derive(Keypath, Debug, Clone, PartialEq)]
+struct Employee {
+    name: String,
+    department: String,
+    level: u32,
+    manager: Option<String>,
+}
+
+fn employee(name: &str, department: &str, level: u32, manager: Option<&str>) -> Employee {
+    Employee {
+        name: name.to_string(),
+        department: department.to_string(),
+        level,
+        manager: manager.map(str::to_string),
+    }
+}
+
+fn employees() -> Vec<Employee> {
+    vec![
+        employee("Alice", "Engineering", 3, None),
+        employee("Bob", "Sales", 2, Some("Alice")),
+        employee("Carol", "Engineering", 1, Some("Alice")),
+        employee("Dave", "Engineering", 3, Some("Alice")),
+        employee("Erin", "Sales", 2, Some("Bob")),
+    ]
+}
+
+fn names(employees: &[Employee]) -> Vec<&str> {
+    employees.iter().map(|employee| employee.name.as_str()).collect()
+}
+
+#[test]
+fn test_zipped_predicates() {
+    let department_level = || Employee::department().zip(Employee::level());
+
+    let senior_engineers = filter_by_keypath(employees(), department_level(), |(department, &level)| {
+        department == "Engineering" && level >= 3
+    })
+    .unwrap();
+    assert_eq!(names(&senior_engineers), vec!["Alice", "Dave"]);
+
+    let (sales, others) = partition_by_keypath(employees(), department_level(), |(department, _)| department == "Sales").unwrap();
+    assert_eq!(names(&sales), vec!["Bob", "Erin"]);
+    assert_eq!(others.len(), 3);
+
+    let found = find_by_keypath(employees(), department_level(), |(_, &level)| level == 1).unwrap();
+    assert_eq!(found.unwrap().name, "Carol");
+
+    let employees = employees();
+    let count = employees.count_by_keypath(department_level(), |(department, &level)| department == "Sales" && level == 2).unwrap();
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn test_zipped_group_and_dedup_keys() {
+    let employees = employees();
+    let zipped = zip_keypaths(Employee::department(), Employee::level());
+
+    let groups = group_by_keypath(&employees, zipped.clone(), |(department, &level)| (department.clone(), level)).unwrap();
+    assert_eq!(groups.len(), 3);
+    assert_eq!(names(&groups[&("Engineering".to_string(), 3)]), vec!["Alice", "Dave"]);
+
+    let unique = dedup_by_keypath(employees, zipped, KeepOccurrence::First).unwrap();
+    assert_eq!(names(&unique), vec!["Alice", "Bob", "Carol"]);
+}
+
+#[test]
+fn test_zipped_sort_keys() {
+    let mut employees = employees();
+    // Department ascending, then level descending, then name
+    let key = Employee::department().zip(Employee::level()).zip(Employee::name());
+    sort_by_keypath(&mut employees, key, |((d1, l1), n1), ((d2, l2), n2)| d1.cmp(d2).then(l2.cmp(l1)).then(n1.cmp(n2))).unwrap();
+    assert_eq!(names(&employees), vec!["Alice", "Dave", "Carol", "Bob", "Erin"]);
+}
+
+#[test]
+fn test_zipped_value_absent_when_either_part_is() {
+    let employees = employees();
+    let name_manager = Employee::name().zip(Employee::manager());
+
+    assert!(name_manager.get(&employees[0]).is_none());
+    assert_eq!(name_manager.get(&employees[1]), Some((&"Bob".to_string(), &"Alice".to_string())));
+    assert_eq!(name_manager.access_owned(&employees[4]), Some(("Erin".to_string(), "Bob".to_string())));
+}
+
+#[test]
+fn test_type_parameters_keep_value_position() {
+    // The value type stays third, so explicit parameters still name the field type
+    let seniors = filter_by_keypath::<Employee, Employee, u32, _, _>(employees(), Employee::level(), |&level| level >= 3).unwrap();
+    assert_eq!(names(&seniors), vec!["Alice", "Dave"]);
+
+    let by_level = group_by_keypath::<Employee, Employee, u32, _, u32, _>(&employees(), Employee::level(), |&level| level).unwrap();
+    assert_eq!(by_level[&2].len(), 2);
+
+    let pairs = dedup_by_keypath::<Employee, Employee, (String, u32), _>(employees(), Employee::department().zip(Employee::level()), KeepOccurrence::First).unwrap();
+    assert_eq!(names(&pairs), vec!["Alice", "Bob", "Carol"]);
+}